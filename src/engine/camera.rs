@@ -2,31 +2,72 @@
 // See docs/research/camera-system.md for design rationale
 //
 // Camera model:
-//   - A "target" point on the XZ ground plane (Y=0) that the camera looks at
-//   - Fixed pitch (elevation angle) and adjustable yaw (horizontal rotation)
-//   - Zoom by adjusting distance along the look vector
+//   - A "target" point on the XZ plane that the camera looks at, raised to the
+//     terrain surface via follow_ground()
+//   - Pitch (elevation angle), optionally flattening as the camera zooms in,
+//     and adjustable yaw (horizontal rotation)
+//   - Zoom by adjusting distance along the look vector, eased toward the
+//     distance the wheel asks for
 //   - MMB drag pans the target on XZ (grab-the-world feel)
 //   - MMB + RMB drag simultaneously rotates yaw
 //   - Mouse wheel zooms in/out
+//   - Arrow keys pan at a fixed speed
 //   - Edge scrolling moves target when mouse is near screen edges (disabled during MMB drag)
+//   - fly_to() glides to a saved CameraView (bookmarks, event jumps); input
+//     is ignored until it lands. follow() keeps the target on a moving point.
 
 use glam::{Mat4, Vec2, Vec3};
+use winit::keyboard::KeyCode;
 use super::input::InputState;
+use super::picking::Ray;
+
+/// Where the camera looks from: enough to restore a view exactly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraView {
+    pub target: Vec2,
+    pub distance: f32,
+    pub yaw: f32,
+}
+
+/// An in-progress fly_to(): eases from `from` to `to` over `duration` seconds.
+struct Transition {
+    from: CameraView,
+    to: CameraView,
+    elapsed: f32,
+    duration: f32,
+}
 
 pub struct RtsCamera {
     /// Point on the ground plane (X/Z) the camera orbits around.
     /// Private: always clamped to bounds in update(). Use target() to read.
     target: Vec2,
 
-    /// Distance from target along the look direction.
+    /// Terrain height under the target, eased by follow_ground() so the view
+    /// glides over cliffs instead of jumping.
+    ground_height: f32,
+
+    /// Distance from target along the look direction, easing toward
+    /// `target_distance`.
     /// Private: always clamped to [min_distance, max_distance] in update(). Use distance() to read.
     distance: f32,
+    /// Distance the wheel has asked for.
+    target_distance: f32,
     pub min_distance: f32,
     pub max_distance: f32,
 
-    /// Elevation angle in radians (0 = horizontal, PI/2 = straight down)
+    /// Rate (1/s) of the exponential ease from the current distance to the
+    /// wheel's target distance; higher is snappier, 0 = instant.
+    pub zoom_smoothing: f32,
+
+    /// Elevation angle in radians (0 = horizontal, PI/2 = straight down) at
+    /// max_distance, and at every distance when `zoomed_in_pitch` is None.
     pub pitch: f32,
 
+    /// Elevation angle at min_distance. When set, the pitch blends linearly
+    /// from this to `pitch` across the zoom range, so close-ups look across
+    /// the battlefield rather than down on it.
+    pub zoomed_in_pitch: Option<f32>,
+
     /// Horizontal rotation in radians (0 = looking along -Z axis)
     pub yaw: f32,
 
@@ -44,22 +85,34 @@ pub struct RtsCamera {
     /// Edge scrolling speed in world units per second
     pub edge_scroll_speed: f32,
 
+    /// Arrow-key panning speed in world units per second
+    pub key_pan_speed: f32,
+
     /// How many pixels from the screen edge trigger edge scrolling
     pub edge_scroll_margin: f32,
 
     /// Map bounds: target is clamped to [bounds_min, bounds_max] on X/Z
     pub bounds_min: Vec2,
     pub bounds_max: Vec2,
+
+    /// Seconds fly_to() takes to reach its destination
+    pub transition_seconds: f32,
+
+    transition: Option<Transition>,
 }
 
 impl RtsCamera {
     pub fn new() -> Self {
         Self {
             target: Vec2::ZERO,
+            ground_height: 0.0,
             distance: 30.0,
+            target_distance: 30.0,
             min_distance: 10.0,
             max_distance: 60.0,
+            zoom_smoothing: 12.0,
             pitch: 55.0_f32.to_radians(),
+            zoomed_in_pitch: None,
             yaw: 0.0,
             fov: 20.0_f32.to_radians(),
             near: 0.1,
@@ -67,14 +120,22 @@ impl RtsCamera {
             rotate_sensitivity: 0.005,  // radians per pixel
             zoom_speed: 3.0,
             edge_scroll_speed: 15.0,
+            key_pan_speed: 20.0,
             edge_scroll_margin: 20.0,
             bounds_min: Vec2::new(-50.0, -50.0),
             bounds_max: Vec2::new(50.0, 50.0),
+            transition_seconds: 0.5,
+            transition: None,
         }
     }
 
     /// Update camera based on input. Call once per frame before rendering.
     pub fn update(&mut self, input: &InputState, dt: f32) {
+        if self.transition.is_some() {
+            self.advance_transition(dt);
+            return;
+        }
+
         // Camera-relative directions on the XZ plane.
         // yaw=0 faces along -Z, so forward=(0,-1) and right=(1,0) in (X,Z).
         let forward = Vec2::new(-self.yaw.sin(), -self.yaw.cos());
@@ -91,7 +152,7 @@ impl RtsCamera {
             // size of the ground plane at the current zoom and pitch.
             let wh = input.window_size.1 as f32;
             if wh > 0.0 {
-                let pan_scale = 2.0 * self.distance * self.pitch.cos()
+                let pan_scale = 2.0 * self.distance * self.current_pitch().cos()
                     * (self.fov * 0.5).tan() / wh;
                 // Drag right → target moves right; drag down → target moves backward.
                 self.target -= right * dx * pan_scale;
@@ -117,23 +178,108 @@ impl RtsCamera {
             }
         }
 
-        // Zoom: scroll up (positive delta) zooms in (decreases distance)
-        self.distance -= input.scroll_delta * self.zoom_speed;
+        // Arrow keys pan regardless of mouse state
+        let mut key_dir = Vec2::ZERO;
+        if input.is_key_held(KeyCode::ArrowLeft)  { key_dir -= right; }
+        if input.is_key_held(KeyCode::ArrowRight) { key_dir += right; }
+        if input.is_key_held(KeyCode::ArrowUp)    { key_dir += forward; }
+        if input.is_key_held(KeyCode::ArrowDown)  { key_dir -= forward; }
+        if key_dir != Vec2::ZERO {
+            self.target += key_dir.normalize() * self.key_pan_speed * dt;
+        }
+
+        // Zoom: scroll up (positive delta) zooms in (decreases distance).
+        // The wheel moves the target distance; the view eases after it.
+        self.target_distance -= input.scroll_delta * self.zoom_speed;
+        self.target_distance = self.target_distance.clamp(self.min_distance, self.max_distance);
+        self.distance = if self.zoom_smoothing > 0.0 {
+            let t = 1.0 - (-self.zoom_smoothing * dt).exp();
+            self.distance + (self.target_distance - self.distance) * t
+        } else {
+            self.target_distance
+        };
         self.distance = self.distance.clamp(self.min_distance, self.max_distance);
 
         // Clamp target to map bounds
         self.target = self.target.clamp(self.bounds_min, self.bounds_max);
     }
 
+    /// Glide to `view` over `transition_seconds` (smoothstep eased), turning
+    /// the short way round. Replaces any flight in progress.
+    pub fn fly_to(&mut self, view: CameraView) {
+        let from = self.view();
+        use std::f32::consts::{PI, TAU};
+        // Shift the requested yaw by whole turns so it lies within half a turn
+        // of the current one; the flight then ends on exactly `view.yaw` when
+        // no wrap is needed.
+        let turn = (view.yaw - from.yaw + PI).rem_euclid(TAU) - PI;
+        let wraps = ((from.yaw + turn - view.yaw) / TAU).round();
+        let to = CameraView {
+            target: view.target.clamp(self.bounds_min, self.bounds_max),
+            distance: view.distance.clamp(self.min_distance, self.max_distance),
+            yaw: view.yaw + wraps * TAU,
+        };
+        self.transition = Some(Transition { from, to, elapsed: 0.0, duration: self.transition_seconds });
+    }
+
+    /// Keep the target on `point` (a followed unit). Call once per frame after
+    /// update(); during a flight the destination tracks the point instead.
+    pub fn follow(&mut self, point: Vec2) {
+        let point = point.clamp(self.bounds_min, self.bounds_max);
+        match &mut self.transition {
+            Some(transition) => transition.to.target = point,
+            None => self.target = point,
+        }
+    }
+
+    /// The current view, for bookmarking.
+    pub fn view(&self) -> CameraView {
+        CameraView { target: self.target, distance: self.distance, yaw: self.yaw }
+    }
+
+    fn advance_transition(&mut self, dt: f32) {
+        let Some(transition) = &mut self.transition else { return };
+        transition.elapsed += dt;
+        let t = if transition.duration > 0.0 { (transition.elapsed / transition.duration).min(1.0) } else { 1.0 };
+        let eased = t * t * (3.0 - 2.0 * t);
+        let (from, to) = (transition.from, transition.to);
+        // Land on the destination itself rather than a rounded blend of it.
+        let view = if t >= 1.0 {
+            self.transition = None;
+            to
+        } else {
+            CameraView {
+                target: from.target.lerp(to.target, eased),
+                distance: from.distance + (to.distance - from.distance) * eased,
+                yaw: from.yaw + (to.yaw - from.yaw) * eased,
+            }
+        };
+        self.target = view.target;
+        self.distance = view.distance;
+        self.yaw = view.yaw;
+        self.target_distance = self.distance;
+    }
+
+    /// Ease the look-at height toward the terrain `height` under the target.
+    /// Call once per frame after update().
+    pub fn follow_ground(&mut self, height: f32, dt: f32) {
+        const FOLLOW_RATE: f32 = 6.0;
+        self.ground_height += (height - self.ground_height) * (FOLLOW_RATE * dt).min(1.0);
+    }
+
+    /// World-space point the camera looks at, on the terrain surface.
+    pub fn target_3d(&self) -> Vec3 {
+        Vec3::new(self.target.x, self.ground_height, self.target.y)
+    }
+
     /// World-space position of the camera eye.
     pub fn camera_position(&self) -> Vec3 {
-        let target_3d = Vec3::new(self.target.x, 0.0, self.target.y);
-        target_3d + self.eye_offset()
+        self.target_3d() + self.eye_offset()
     }
 
     /// View matrix: looks from the camera eye toward the target.
     pub fn view_matrix(&self) -> Mat4 {
-        Mat4::look_at_rh(self.camera_position(), Vec3::new(self.target.x, 0.0, self.target.y), Vec3::Y)
+        Mat4::look_at_rh(self.camera_position(), self.target_3d(), Vec3::Y)
     }
 
     /// Perspective projection matrix.
@@ -146,9 +292,30 @@ impl RtsCamera {
         self.projection_matrix(aspect) * self.view_matrix()
     }
 
+    /// Ray from the eye through window pixel `pixel` (origin top-left) for a
+    /// window of `window_size` pixels. Inverts `view_projection`, so it picks
+    /// exactly what is drawn under that pixel.
+    pub fn screen_ray(&self, pixel: Vec2, window_size: (u32, u32)) -> Ray {
+        let size = Vec2::new(window_size.0.max(1) as f32, window_size.1.max(1) as f32);
+        let ndc = Vec2::new(pixel.x / size.x * 2.0 - 1.0, 1.0 - pixel.y / size.y * 2.0);
+        let inverse = self.view_projection(size.x / size.y).inverse();
+        // wgpu clip depth runs 0 (near plane) to 1 (far plane).
+        let near = inverse.project_point3(ndc.extend(0.0));
+        let far = inverse.project_point3(ndc.extend(1.0));
+        Ray::new(near, far - near)
+    }
+
     pub fn target(&self) -> Vec2 { self.target }
     pub fn distance(&self) -> f32 { self.distance }
 
+    /// Elevation angle at the current distance (see `zoomed_in_pitch`).
+    pub fn current_pitch(&self) -> f32 {
+        match self.zoomed_in_pitch {
+            Some(zoomed_in) => zoomed_in + (self.pitch - zoomed_in) * (1.0 - self.zoom_fraction()),
+            None => self.pitch,
+        }
+    }
+
     /// Zoom fraction in [0, 1]: 1 = fully zoomed in (min_distance), 0 = fully zoomed out.
     /// Matches player intuition: zoom 100% = closest view.
     pub fn zoom_fraction(&self) -> f32 {
@@ -162,10 +329,43 @@ impl RtsCamera {
 
     // Offset from target to camera eye based on pitch, yaw, and distance.
     fn eye_offset(&self) -> Vec3 {
+        let pitch = self.current_pitch();
         Vec3::new(
-            self.yaw.sin() * self.pitch.cos() * self.distance,
-            self.pitch.sin() * self.distance,
-            self.yaw.cos() * self.pitch.cos() * self.distance,
+            self.yaw.sin() * pitch.cos() * self.distance,
+            pitch.sin() * self.distance,
+            self.yaw.cos() * pitch.cos() * self.distance,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fly_to_turns_the_short_way_round() {
+        let mut camera = RtsCamera::new();
+        camera.yaw = 350f32.to_radians();
+        camera.fly_to(CameraView { target: Vec2::ZERO, distance: 30.0, yaw: 10f32.to_radians() });
+        camera.advance_transition(camera.transition_seconds / 2.0);
+        // Halfway through a 20° turn across north, not a 340° turn back.
+        assert!((camera.yaw.to_degrees() - 360.0).abs() < 1e-3, "yaw {}", camera.yaw.to_degrees());
+        camera.advance_transition(camera.transition_seconds);
+        assert!((camera.yaw.to_degrees() - 370.0).abs() < 1e-3, "yaw {}", camera.yaw.to_degrees());
+    }
+
+    #[test]
+    fn transition_lands_on_the_destination_and_ends() {
+        let mut camera = RtsCamera::new();
+        let destination = CameraView { target: Vec2::new(12.3, -7.1), distance: 41.7, yaw: 0.9 };
+        camera.fly_to(destination);
+        // Uneven steps that overshoot the duration.
+        for dt in [0.13, 0.17, 0.11, 0.23] {
+            camera.advance_transition(dt);
+        }
+        assert!(camera.transition.is_none());
+        let view = camera.view();
+        assert_eq!((view.target, view.distance, view.yaw), (destination.target, destination.distance, destination.yaw));
+        assert_eq!(camera.target_distance, destination.distance);
+    }
+}
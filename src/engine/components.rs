@@ -2,25 +2,44 @@
 // These are reusable across any game built with this engine
 
 use bevy_ecs::prelude::*;
-use glam::Vec3;
+use glam::{Mat4, Quat, Vec3};
+use super::skin::{SkinGraph, SkinSocket, SocketPose};
 
-/// Position of an entity in 3D space
+/// Position, orientation and scale of an entity in 3D space
 #[derive(Component, Debug, Clone, Copy)]
 pub struct Transform {
     pub position: Vec3,
+    pub rotation: Quat,
+    /// Per-axis scale in model space. Keep components > 0 (normals divide by it).
+    pub scale: Vec3,
 }
 
 impl Default for Transform {
     fn default() -> Self {
         Self {
             position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
         }
     }
 }
 
 impl Transform {
     pub fn from_position(position: Vec3) -> Self {
-        Self { position }
+        Self { position, ..Default::default() }
+    }
+
+    /// Model matrix: scale, then rotate, then translate.
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.position)
+    }
+
+    /// Yaw-only rotation that points the model's forward axis (-Z) along `dir` on XZ.
+    /// Returns `None` if `dir` has no horizontal component.
+    pub fn facing_rotation(dir: Vec3) -> Option<Quat> {
+        let flat = Vec3::new(dir.x, 0.0, dir.z);
+        if flat.length_squared() < 1e-6 { return None; }
+        Some(Quat::from_rotation_y((-flat.x).atan2(-flat.z)))
     }
 }
 
@@ -32,6 +51,29 @@ pub struct Color {
     pub b: f32,
 }
 
+/// Self-illumination strength (multiple of the entity's color). Glows regardless of lighting.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Emissive {
+    pub strength: f32,
+}
+
+/// Point light carried by an entity, e.g. an energy core or torch.
+/// `offset` is in model space, so it follows the entity's rotation and scale.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PointLightEmitter {
+    pub color: [f32; 3],
+    pub radius: f32,
+    pub offset: Vec3,
+}
+
+/// Which registered mesh an entity renders with. Issued by `MeshRegistry::register`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshHandle(pub u32);
+
+/// Which registered material an entity renders with. Issued by `Renderer::register_material`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialHandle(pub u32);
+
 /// Velocity of an entity in 3D space (units per second)
 #[derive(Component, Debug, Clone, Copy)]
 pub struct Velocity {
@@ -59,6 +101,48 @@ pub struct UnitAgent {
     pub priority:  u32,
 }
 
+/// Part of the player's current selection. Draws a selection ring and keeps
+/// the health bar visible.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Selected;
+
+/// Under the mouse cursor this frame (at most one entity).
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Hovered;
+
+/// Hit points plus a shield that absorbs damage first.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub hp: f32,
+    pub max_hp: f32,
+    pub shield: f32,
+    /// 0 for units without a shield.
+    pub max_shield: f32,
+    /// Seconds since the last hit; drives "show bar on damage".
+    pub since_damage: f32,
+}
+
+impl Health {
+    pub fn new(max_hp: f32, max_shield: f32) -> Self {
+        Self { hp: max_hp, max_hp, shield: max_shield, max_shield, since_damage: f32::INFINITY }
+    }
+
+    /// Apply `amount` damage: the shield soaks what it can, the rest hits hp.
+    pub fn damage(&mut self, amount: f32) {
+        let absorbed = amount.min(self.shield);
+        self.shield -= absorbed;
+        self.hp = (self.hp - (amount - absorbed)).max(0.0);
+        self.since_damage = 0.0;
+    }
+
+    pub fn hp_fraction(&self) -> f32 { self.hp / self.max_hp }
+
+    /// `None` for unshielded units.
+    pub fn shield_fraction(&self) -> Option<f32> {
+        (self.max_shield > 0.0).then(|| self.shield / self.max_shield)
+    }
+}
+
 /// Fixed XZ offset from the group centroid assigned at spawn time.
 ///
 /// The formation system uses this to compute each unit's slot target every
@@ -68,3 +152,44 @@ pub struct UnitAgent {
 pub struct FormationOffset {
     pub offset: glam::Vec2,
 }
+
+/// Model-space node positions of an entity's SkinGraph.
+///
+/// Starts at the graph's rest pose; posing/animation writes new positions here
+/// and the entity's `AttachmentSockets` follow on the next query.
+#[derive(Component, Debug, Clone)]
+pub struct SkinPose {
+    pub nodes: Vec<Vec3>,
+}
+
+impl SkinPose {
+    pub fn rest(graph: &SkinGraph) -> Self {
+        Self { nodes: graph.node_positions() }
+    }
+}
+
+/// Attachment sockets for an entity, bound to nodes of its SkinGraph.
+///
+/// Only the bindings (node, offset, orientation) are stored; `resolve` places
+/// them against the entity's current `SkinPose`.
+#[derive(Component, Debug, Clone)]
+pub struct AttachmentSockets {
+    pub sockets: Vec<SkinSocket>,
+}
+
+impl AttachmentSockets {
+    pub fn from_graph(graph: &SkinGraph) -> Self {
+        Self { sockets: graph.sockets.clone() }
+    }
+
+    /// Model-space pose of every socket against `pose`, in declaration order.
+    pub fn resolve<'a>(&'a self, pose: &'a SkinPose) -> impl Iterator<Item = SocketPose> + 'a {
+        self.sockets.iter().map(|s| s.resolve(&pose.nodes))
+    }
+}
+
+/// Model-space socket pose → world matrix for an entity at `transform`.
+/// The socket inherits the entity's rotation and scale.
+pub fn socket_world_matrix(transform: &Transform, socket: &SocketPose) -> Mat4 {
+    transform.matrix() * Mat4::from_rotation_translation(socket.rotation, socket.position)
+}
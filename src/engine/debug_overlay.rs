@@ -1,4 +1,5 @@
 use egui::epaint::Shadow;
+use super::renderer::{FrameContext, RenderSettings};
 
 pub struct DebugStats {
     pub fps: u32,
@@ -7,14 +8,42 @@ pub struct DebugStats {
     pub frame_time_max_ms: f32,
     pub entity_count: usize,
     pub draw_calls: u32,
+    /// Instances drawn in the main pass / shadow pass only / culled entirely last frame.
+    pub instances_visible: u32,
+    pub instances_shadow_only: u32,
+    pub instances_culled: u32,
+    /// Current instance buffer allocation in bytes.
+    pub instance_buffer_bytes: u64,
+    /// Times the instance buffer has grown since startup.
+    pub instance_buffer_reallocs: u32,
+    /// Point lights shaded last frame (capped) and submitted before the cap.
+    pub point_lights_shaded: u32,
+    pub point_lights_submitted: u32,
+    /// Live particles and those drawn after culling last frame.
+    pub particles_alive: usize,
+    pub particles_drawn: u32,
+    /// Live ground decals, and whether the placement preview (B) is shown.
+    pub decals: usize,
+    pub placement_preview: bool,
     pub resolution: (u32, u32),
     pub camera_target: (f32, f32),
+    /// Ground X/Z under the mouse cursor; `None` when the cursor is over the sky.
+    pub cursor_ground: Option<(f32, f32)>,
     pub camera_distance: f32,
     pub camera_zoom_pct: f32,
+    pub camera_pitch_deg: f32,
+    /// Whether the camera follows a unit (F), and bookmarks stored (Ctrl+F1/F2/F6–F9).
+    pub camera_following: bool,
+    pub camera_bookmarks: usize,
+    /// Events in the log (Space cycles) and the newest one's message.
+    pub events: usize,
+    pub last_event: Option<String>,
     /// Time spent on the last flowfield recomputation pass (ms). 0 if not yet run.
     pub pathfinding_ms: f32,
     /// Total number of flowfield recomputes since startup.
     pub flowfield_recomputes: u32,
+    /// Current health bar visibility rule (cycled with H).
+    pub health_bars: &'static str,
 }
 
 /// One unit's debug draw data, already projected to egui screen points.
@@ -25,6 +54,8 @@ pub struct UnitDebugDraw {
     pub vel_tip: egui::Pos2,
     /// Avoidance-radius circle size in screen points.
     pub radius_px: f32,
+    /// Attachment-socket positions in egui screen points.
+    pub sockets: Vec<egui::Pos2>,
 }
 
 /// One flowfield cell's direction arrow, already projected to egui screen points.
@@ -51,6 +82,22 @@ pub struct DensityCell {
     pub intensity: f32,
 }
 
+/// Per-frame inputs to `DebugOverlay::render()`: the optional debug layers.
+/// A `None` layer is hidden.
+#[derive(Default)]
+pub struct DebugLayers<'a> {
+    /// F3 stats panel.
+    pub stats: Option<&'a DebugStats>,
+    /// Quality controls under the stats panel, edited in place.
+    pub settings: Option<&'a mut RenderSettings>,
+    /// F4 per-unit radius circles, velocity arrows + sockets.
+    pub unit_draws: Option<&'a [UnitDebugDraw]>,
+    /// F5 per-cell flowfield direction arrows.
+    pub flowfield_arrows: Option<&'a [FlowfieldArrowDraw]>,
+    /// F5 density heatmap squares.
+    pub density_cells: Option<&'a [DensityCell]>,
+}
+
 pub struct DebugOverlay {
     pub visible: bool,
     egui_ctx: egui::Context,
@@ -115,27 +162,20 @@ impl DebugOverlay {
         self.egui_state.on_window_event(window, event)
     }
 
-    /// Render one egui frame covering all optional debug layers:
-    ///
-    /// - `density_cells`    — F5 density heatmap squares (`None` = hidden).
-    /// - `flowfield_arrows` — F5 per-cell flowfield direction arrows (`None` = hidden).
-    /// - `unit_draws`       — F4 per-unit radius circles + velocity arrows (`None` = hidden).
-    /// - `stats`            — F3 stats panel (`None` = hidden).
+    /// Render one egui frame covering all of `layers` into `frame`.
     ///
-    /// All layers are tessellated in a single egui pass for efficiency.
+    /// Density cells are drawn first (behind everything), then flowfield
+    /// arrows, unit draws and the stats panel. All layers are tessellated in a
+    /// single egui pass for efficiency.
     pub fn render(
         &mut self,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        encoder: &mut wgpu::CommandEncoder,
+        frame: FrameContext,
         window: &winit::window::Window,
-        view: &wgpu::TextureView,
         screen_descriptor: &egui_wgpu::ScreenDescriptor,
-        stats: Option<&DebugStats>,
-        unit_draws: Option<&[UnitDebugDraw]>,
-        flowfield_arrows: Option<&[FlowfieldArrowDraw]>,
-        density_cells: Option<&[DensityCell]>,
+        layers: DebugLayers,
     ) {
+        let FrameContext { device, queue, encoder, view } = frame;
+        let DebugLayers { stats, mut settings, unit_draws, flowfield_arrows, density_cells } = layers;
         let raw_input = self.egui_state.take_egui_input(window);
 
         let full_output = self.egui_ctx.run(raw_input, |ctx| {
@@ -208,6 +248,14 @@ impl DebugOverlay {
                         2.5,
                         egui::Color32::from_rgba_unmultiplied(80, 255, 140, 220),
                     );
+                    // Attachment sockets
+                    for &socket in &draw.sockets {
+                        painter.circle_filled(
+                            socket,
+                            2.0,
+                            egui::Color32::from_rgba_unmultiplied(255, 80, 255, 220),
+                        );
+                    }
                 }
             }
 
@@ -230,20 +278,82 @@ impl DebugOverlay {
                                 ));
                                 ui.label(format!("Entities: {}", stats.entity_count));
                                 ui.label(format!("Draw calls: {}", stats.draw_calls));
+                                ui.label(format!(
+                                    "Culling: {} visible  {} shadow-only  {} culled",
+                                    stats.instances_visible,
+                                    stats.instances_shadow_only,
+                                    stats.instances_culled,
+                                ));
+                                ui.label(format!(
+                                    "Instance buffer: {:.1} KiB  Reallocs: {}",
+                                    stats.instance_buffer_bytes as f32 / 1024.0,
+                                    stats.instance_buffer_reallocs,
+                                ));
+                                ui.label(format!(
+                                    "Point lights: {} / {}",
+                                    stats.point_lights_shaded,
+                                    stats.point_lights_submitted,
+                                ));
+                                ui.label(format!(
+                                    "Particles: {} drawn / {} alive",
+                                    stats.particles_drawn,
+                                    stats.particles_alive,
+                                ));
+                                ui.label(format!(
+                                    "Decals: {}  Placement preview: {} (B)",
+                                    stats.decals,
+                                    if stats.placement_preview { "on" } else { "off" },
+                                ));
                                 ui.label(format!(
                                     "Resolution: {} x {}",
                                     stats.resolution.0, stats.resolution.1
                                 ));
                                 ui.label(format!(
-                                    "Camera: ({:.1}, {:.1})  dist {:.1}  zoom {:.0}%",
+                                    "Camera: ({:.1}, {:.1})  dist {:.1}  zoom {:.0}%  pitch {:.0}°",
                                     stats.camera_target.0, stats.camera_target.1,
-                                    stats.camera_distance, stats.camera_zoom_pct
+                                    stats.camera_distance, stats.camera_zoom_pct, stats.camera_pitch_deg
                                 ));
+                                ui.label(format!(
+                                    "Follow: {} (F)  Bookmarks: {}/6 (Ctrl+F1/F2/F6–F9)",
+                                    if stats.camera_following { "on" } else { "off" },
+                                    stats.camera_bookmarks,
+                                ));
+                                ui.label(format!(
+                                    "Events: {} (Space)  Last: {}",
+                                    stats.events,
+                                    stats.last_event.as_deref().unwrap_or("none"),
+                                ));
+                                ui.label(match stats.cursor_ground {
+                                    Some((x, z)) => format!("Cursor: ({x:.1}, {z:.1})"),
+                                    None => "Cursor: no ground".to_string(),
+                                });
                                 ui.label(format!(
                                     "Pathfinding: {:.2} ms  Recomputes: {}",
                                     stats.pathfinding_ms,
                                     stats.flowfield_recomputes,
                                 ));
+                                ui.label(format!("Health bars: {} (H)  K: damage selected", stats.health_bars));
+
+                                // ── Quality settings (applied by the caller) ──
+                                if let Some(settings) = settings.as_deref_mut() {
+                                    ui.separator();
+                                    ui.horizontal(|ui| {
+                                        ui.label("MSAA:");
+                                        for samples in [1, 2, 4] {
+                                            let text = if samples == 1 { "off".to_string() } else { format!("{samples}x") };
+                                            ui.radio_value(&mut settings.msaa_samples, samples, text);
+                                        }
+                                    });
+                                    ui.checkbox(&mut settings.shadows, "Shadows");
+                                    ui.add(egui::Slider::new(&mut settings.lod_bias, -2.0..=2.0).text("LOD bias"));
+                                    ui.horizontal(|ui| {
+                                        ui.checkbox(&mut settings.bloom, "Bloom");
+                                        ui.checkbox(&mut settings.tone_mapping, "Tone map");
+                                        ui.checkbox(&mut settings.color_grading, "Grade");
+                                    });
+                                    ui.add_enabled(settings.bloom, egui::Slider::new(&mut settings.bloom_threshold, 0.2..=3.0).text("Bloom threshold"));
+                                    ui.add_enabled(settings.bloom, egui::Slider::new(&mut settings.bloom_intensity, 0.0..=2.0).text("Bloom intensity"));
+                                }
                             });
                     });
             }
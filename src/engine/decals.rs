@@ -0,0 +1,138 @@
+// Ground decals: building placement previews, rally flags and scorch marks.
+// See docs/research/rendering-architecture.md §"Decals".
+//
+// A decal is an axis-aligned rectangle on the ground with a procedural shape
+// (renderer/decals.rs draws the pattern). `Decals::submit` drapes each one
+// over the terrain as a grid of small cells lifted just above the surface, so
+// it follows ramps and hills instead of cutting through them. Timed decals
+// fade out over the end of their lifetime and are dropped by `update`.
+
+use glam::{Vec2, Vec3};
+use super::renderer::{DecalShape, Renderer};
+use super::terrain::Terrain;
+
+/// Decals sit this far above the ground to stay clear of z-fighting.
+const DECAL_LIFT: f32 = 0.03;
+/// Target edge length of one draped cell, in world units.
+const DECAL_CELL: f32 = 0.5;
+/// Cell cap per axis, so huge decals stay cheap.
+const MAX_CELLS: u32 = 32;
+
+/// Handle to a decal in `Decals`. Stale once the decal expires or is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecalId(u64);
+
+#[derive(Debug, Clone)]
+pub struct Decal {
+    pub shape: DecalShape,
+    /// World X/Z of the center.
+    pub center: Vec2,
+    /// Extent along world X and Z from the center.
+    pub half_size: Vec2,
+    /// Linear color; alpha scales the whole pattern.
+    pub color: [f32; 4],
+    /// Seconds until removal; infinite for permanent decals.
+    pub lifetime: f32,
+    /// Seconds at the end of the lifetime over which alpha fades to zero.
+    pub fade: f32,
+    age: f32,
+}
+
+impl Decal {
+    /// A permanent decal.
+    pub fn new(shape: DecalShape, center: Vec2, half_size: Vec2, color: [f32; 4]) -> Self {
+        Self { shape, center, half_size, color, lifetime: f32::INFINITY, fade: 0.0, age: 0.0 }
+    }
+
+    /// Expire after `lifetime` seconds, fading out over the last `fade` of them.
+    pub fn with_lifetime(mut self, lifetime: f32, fade: f32) -> Self {
+        self.lifetime = lifetime;
+        self.fade = fade.min(lifetime);
+        self
+    }
+
+    /// Alpha multiplier: 1 until the fade starts, then linearly down to 0.
+    pub fn opacity(&self) -> f32 {
+        let remaining = self.lifetime - self.age;
+        if remaining >= self.fade {
+            1.0
+        } else {
+            (remaining / self.fade).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Every live decal, in submission (and therefore blend) order.
+pub struct Decals {
+    decals: Vec<(DecalId, Decal)>,
+    next_id: u64,
+}
+
+impl Decals {
+    pub fn new() -> Self {
+        Self { decals: Vec::new(), next_id: 0 }
+    }
+
+    pub fn len(&self) -> usize { self.decals.len() }
+
+    /// Add a decal on top of the existing ones.
+    pub fn add(&mut self, decal: Decal) -> DecalId {
+        let id = DecalId(self.next_id);
+        self.next_id += 1;
+        self.decals.push((id, decal));
+        id
+    }
+
+    /// Remove a decal; returns false if it was already gone.
+    pub fn remove(&mut self, id: DecalId) -> bool {
+        let before = self.decals.len();
+        self.decals.retain(|(d, _)| *d != id);
+        self.decals.len() != before
+    }
+
+    pub fn get_mut(&mut self, id: DecalId) -> Option<&mut Decal> {
+        self.decals.iter_mut().find(|(d, _)| *d == id).map(|(_, decal)| decal)
+    }
+
+    /// Age every decal by `dt` and drop the expired ones.
+    pub fn update(&mut self, dt: f32) {
+        self.decals.retain_mut(|(_, decal)| {
+            decal.age += dt;
+            decal.age < decal.lifetime
+        });
+    }
+
+    /// Queue every decal, draped over `terrain`, for the next render().
+    pub fn submit(&self, renderer: &mut Renderer, terrain: &Terrain) {
+        let mut triangles = Vec::new();
+        for (_, decal) in &self.decals {
+            let mut color = decal.color;
+            color[3] *= decal.opacity();
+            if color[3] <= 0.0 {
+                continue;
+            }
+            drape(decal, terrain, &mut triangles);
+            renderer.submit_decal(decal.shape, decal.half_size, color, &triangles);
+            triangles.clear();
+        }
+    }
+}
+
+/// Tessellate `decal` into a grid of cells whose corners sit on the terrain,
+/// as a triangle list of (world position, offset from center).
+fn drape(decal: &Decal, terrain: &Terrain, out: &mut Vec<(Vec3, Vec2)>) {
+    let size = decal.half_size * 2.0;
+    let cells_x = ((size.x / DECAL_CELL).ceil() as u32).clamp(1, MAX_CELLS);
+    let cells_z = ((size.y / DECAL_CELL).ceil() as u32).clamp(1, MAX_CELLS);
+    let vertex = |i: u32, j: u32| {
+        let local = Vec2::new(i as f32 / cells_x as f32, j as f32 / cells_z as f32) * size - decal.half_size;
+        let world = decal.center + local;
+        (Vec3::new(world.x, terrain.height_at(world.x, world.y) + DECAL_LIFT, world.y), local)
+    };
+    for j in 0..cells_z {
+        for i in 0..cells_x {
+            let (a, b, c, d) = (vertex(i, j), vertex(i + 1, j), vertex(i, j + 1), vertex(i + 1, j + 1));
+            out.extend([a, c, b, b, c, d]);
+        }
+    }
+}
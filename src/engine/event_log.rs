@@ -0,0 +1,102 @@
+// Event log: recent notable happenings with a map location, for the
+// "jump to last event" key (Space in GAME_DESIGN.md).
+//
+// The log keeps the newest MAX_EVENTS entries. `cycle` walks them newest
+// first; repeated presses step to older events and wrap around, and any new
+// event restarts the walk at itself.
+
+use std::collections::VecDeque;
+use glam::Vec2;
+
+/// Entries kept before the oldest is dropped.
+const MAX_EVENTS: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct GameEvent {
+    pub message: String,
+    /// World X/Z the camera should jump to.
+    pub position: Vec2,
+}
+
+pub struct EventLog {
+    /// Newest at the front.
+    events: VecDeque<GameEvent>,
+    /// Index of the event the last `cycle` returned; `None` until the first
+    /// cycle after a new event.
+    cursor: Option<usize>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self { events: VecDeque::with_capacity(MAX_EVENTS), cursor: None }
+    }
+
+    pub fn len(&self) -> usize { self.events.len() }
+
+    pub fn push(&mut self, message: impl Into<String>, position: Vec2) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_back();
+        }
+        self.events.push_front(GameEvent { message: message.into(), position });
+        self.cursor = None;
+    }
+
+    /// Newest event, if any.
+    pub fn latest(&self) -> Option<&GameEvent> { self.events.front() }
+
+    /// The next event to jump to: the newest on the first call after a new
+    /// event, then each older one in turn, wrapping back to the newest.
+    pub fn cycle(&mut self) -> Option<&GameEvent> {
+        if self.events.is_empty() {
+            return None;
+        }
+        let next = self.cursor.map_or(0, |i| (i + 1) % self.events.len());
+        self.cursor = Some(next);
+        self.events.get(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_with(count: usize) -> EventLog {
+        let mut log = EventLog::new();
+        for i in 0..count {
+            log.push(format!("event {i}"), Vec2::new(i as f32, 0.0));
+        }
+        log
+    }
+
+    fn cycled(log: &mut EventLog, presses: usize) -> Vec<String> {
+        (0..presses).map(|_| log.cycle().unwrap().message.clone()).collect()
+    }
+
+    #[test]
+    fn cycle_walks_newest_first_and_wraps() {
+        let mut log = log_with(3);
+        assert_eq!(cycled(&mut log, 4), ["event 2", "event 1", "event 0", "event 2"]);
+    }
+
+    #[test]
+    fn push_restarts_the_cycle() {
+        let mut log = log_with(3);
+        cycled(&mut log, 2);
+        log.push("event 3", Vec2::ZERO);
+        assert_eq!(cycled(&mut log, 2), ["event 3", "event 2"]);
+    }
+
+    #[test]
+    fn cycle_on_an_empty_log_is_none() {
+        assert!(EventLog::new().cycle().is_none());
+    }
+
+    #[test]
+    fn oldest_events_are_evicted() {
+        let mut log = log_with(MAX_EVENTS + 3);
+        assert_eq!(log.len(), MAX_EVENTS);
+        assert_eq!(log.latest().unwrap().message, format!("event {}", MAX_EVENTS + 2));
+        let oldest = cycled(&mut log, MAX_EVENTS).pop().unwrap();
+        assert_eq!(oldest, "event 3");
+    }
+}
@@ -0,0 +1,93 @@
+// View frustum extracted from a view-projection matrix, for CPU culling.
+// See docs/research/rendering-architecture.md §"Frustum culling".
+//
+// Planes come straight from the rows of the combined matrix (Gribb–Hartmann),
+// using wgpu's clip-space depth range [0, 1]. Normals point into the frustum.
+
+use glam::{Mat4, Vec3, Vec4};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near, far — as (normal.xyz, d) with |normal| = 1.
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extract the six planes from a combined view-projection matrix
+    /// (e.g. `RtsCamera::view_projection`).
+    pub fn from_view_projection(view_proj: Mat4) -> Self {
+        let r0 = view_proj.row(0);
+        let r1 = view_proj.row(1);
+        let r2 = view_proj.row(2);
+        let r3 = view_proj.row(3);
+        let planes = [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r2, r3 - r2]
+            .map(|p| p / p.truncate().length());
+        Self { planes }
+    }
+
+    /// True if the sphere is at least partly inside. Conservative near corners:
+    /// spheres just outside two planes' intersection may still report true.
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes.iter().all(|p| p.truncate().dot(center) + p.w >= -radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3, Vec4Swizzles};
+    use super::Frustum;
+    use super::super::camera::RtsCamera;
+
+    const ASPECT: f32 = 16.0 / 9.0;
+
+    /// Default camera, its eye, and unit forward/right vectors.
+    fn camera_frame() -> (Frustum, Vec3, Vec3, Vec3) {
+        let camera = RtsCamera::new();
+        let frustum = Frustum::from_view_projection(camera.view_projection(ASPECT));
+        let eye = camera.camera_position();
+        let forward = (camera.target_3d() - eye).normalize();
+        let right = forward.cross(Vec3::Y).normalize();
+        (frustum, eye, forward, right)
+    }
+
+    #[test]
+    fn sphere_in_front_is_inside() {
+        let (frustum, eye, forward, _) = camera_frame();
+        assert!(frustum.intersects_sphere(eye + forward * 30.0, 0.5));
+    }
+
+    #[test]
+    fn spheres_behind_and_beside_are_outside() {
+        let (frustum, eye, forward, right) = camera_frame();
+        assert!(!frustum.intersects_sphere(eye - forward * 5.0, 1.0));
+        assert!(!frustum.intersects_sphere(eye + forward * 30.0 + right * 30.0, 1.0));
+        assert!(!frustum.intersects_sphere(eye + forward * 250.0, 1.0));
+    }
+
+    #[test]
+    fn near_plane_uses_zero_to_one_depth() {
+        // Near is 0.1. GL's −1..1 extraction would put it at ~0.05 and keep this.
+        let (frustum, eye, forward, _) = camera_frame();
+        assert!(!frustum.intersects_sphere(eye + forward * 0.07, 0.001));
+        assert!(frustum.intersects_sphere(eye + forward * 0.15, 0.001));
+    }
+
+    #[test]
+    fn straddling_spheres_are_inside() {
+        let (frustum, eye, forward, right) = camera_frame();
+        // Right edge at depth 30 is 30·tan(10°)·aspect ≈ 9.4 to the side.
+        let past_edge = eye + forward * 30.0 + right * 11.0;
+        assert!(!frustum.intersects_sphere(past_edge, 0.5));
+        assert!(frustum.intersects_sphere(past_edge, 3.0));
+        // Straddling the far plane (200).
+        assert!(frustum.intersects_sphere(eye + forward * 205.0, 10.0));
+    }
+
+    #[test]
+    fn planes_are_normalized() {
+        let (frustum, ..) = camera_frame();
+        for plane in frustum.planes {
+            assert!((plane.xyz().length() - 1.0).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// Golden-geometry regression tests for the procedural mesh pipeline.
+//
+// Each reference SkinGraph is run through skin → weld → CC (levels 0–3) →
+// triangulate, and the vertex/face counts and content hashes are compared
+// against tests/golden/meshes.txt. Any change to skin.rs, weld.rs,
+// subdivide.rs or mesh.rs that alters output geometry fails here.
+//
+// Intentional geometry changes: regenerate the golden file with
+//   UPDATE_GOLDEN=1 cargo test golden
+// and review the diff before committing.
+
+use glam::{Quat, Vec3};
+use super::mesh::{ContentHash, PolyMesh, triangulate_smooth, triangulate_limit};
+use super::skin::{SkinGraph, skin_modifier};
+use super::subdivide::subdivide;
+use super::weld::weld;
+
+const GOLDEN_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/meshes.txt");
+const MAX_LEVEL: u32 = 3;
+
+/// Reference skin graphs covered by the golden file.
+fn reference_graphs() -> Vec<(&'static str, SkinGraph)> {
+    let mut unit = SkinGraph::new();
+    let body = unit.add_node(Vec3::ZERO, 0.5);
+    unit.add_socket(body, "overhead", Vec3::new(0.0, 0.5, 0.0), Quat::IDENTITY);
+
+    let mut offset = SkinGraph::new();
+    offset.add_node(Vec3::new(3.0, 1.0, -2.0), 1.0);
+
+    let mut pair = SkinGraph::new();
+    pair.add_node(Vec3::ZERO, 0.5);
+    pair.add_node(Vec3::new(2.0, 0.0, 0.0), 0.25);
+
+    // Two cubes sharing a face: exercises the weld pass.
+    let mut touching = SkinGraph::new();
+    touching.add_node(Vec3::ZERO, 0.5);
+    touching.add_node(Vec3::new(1.0, 0.0, 0.0), 0.5);
+
+    vec![("unit", unit), ("offset", offset), ("pair", pair), ("touching", touching)]
+}
+
+/// One golden-file line per (graph, level).
+fn golden_lines() -> Vec<String> {
+    let mut lines = Vec::new();
+    for (name, graph) in reference_graphs() {
+        let (base, _) = weld(&skin_modifier(&graph), 1e-4);
+        for level in 0..=MAX_LEVEL {
+            let poly = subdivide(&base, level);
+            let smooth = triangulate_smooth(&poly);
+            let limit = triangulate_limit(&poly);
+            lines.push(format!(
+                "{name} L{level} verts={} faces={} indices={} poly={:016x} smooth={:016x} limit={:016x}",
+                poly.vertex_count(),
+                poly.faces.len(),
+                smooth.index_count(),
+                poly.content_hash(),
+                smooth.content_hash(),
+                limit.content_hash(),
+            ));
+        }
+    }
+    lines
+}
+
+#[test]
+fn golden_meshes_match() {
+    let actual = golden_lines();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(GOLDEN_PATH, actual.join("\n") + "\n").expect("write golden file");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(GOLDEN_PATH)
+        .expect("missing tests/golden/meshes.txt — run UPDATE_GOLDEN=1 cargo test golden");
+    let expected: Vec<&str> = expected.lines().collect();
+
+    let mismatches: Vec<String> = actual.iter().zip(expected.iter())
+        .filter(|(a, e)| a.as_str() != **e)
+        .map(|(a, e)| format!("  expected: {e}\n  actual:   {a}"))
+        .collect();
+    assert!(
+        mismatches.is_empty() && actual.len() == expected.len(),
+        "golden geometry changed ({} of {} lines differ, {} expected lines):\n{}",
+        mismatches.len(), actual.len(), expected.len(), mismatches.join("\n"),
+    );
+}
+
+#[test]
+fn hash_ignores_vertex_and_face_order() {
+    let mut graph = SkinGraph::new();
+    graph.add_node(Vec3::ZERO, 0.5);
+    let mesh = subdivide(&skin_modifier(&graph), 1);
+
+    // Reverse vertex order, remap faces, reverse face order and rotate each face.
+    let n = mesh.vertex_count();
+    let mut shuffled = PolyMesh::new();
+    for &p in mesh.positions.iter().rev() {
+        shuffled.add_vertex(p);
+    }
+    for face in mesh.faces.iter().rev() {
+        let mut f: Vec<usize> = face.iter().map(|&v| n - 1 - v).collect();
+        f.rotate_left(1);
+        shuffled.add_face(f);
+    }
+
+    assert_eq!(mesh.content_hash(), shuffled.content_hash());
+}
+
+#[test]
+fn hash_detects_geometry_and_winding_changes() {
+    let mut graph = SkinGraph::new();
+    graph.add_node(Vec3::ZERO, 0.5);
+    let mesh = skin_modifier(&graph);
+    let h = mesh.content_hash();
+
+    // Sub-quantum noise is absorbed.
+    let mut noisy = subdivide(&mesh, 0);
+    noisy.positions[0] += Vec3::splat(1e-6);
+    assert_eq!(h, noisy.content_hash());
+
+    let mut moved = subdivide(&mesh, 0);
+    moved.positions[0].x += 0.01;
+    assert_ne!(h, moved.content_hash());
+
+    let mut flipped = subdivide(&mesh, 0);
+    flipped.faces[0].reverse();
+    assert_ne!(h, flipped.content_hash());
+}
@@ -21,6 +21,8 @@ pub struct InputState {
     pub scroll_delta: f32,
 
     // Mouse buttons
+    /// Left button went down this frame. Reset in end_frame().
+    pub left_mouse_clicked: bool,
     pub middle_mouse_held: bool,
     pub right_mouse_held: bool,
 
@@ -36,6 +38,7 @@ impl InputState {
             mouse_position: (0.0, 0.0),
             mouse_delta: (0.0, 0.0),
             scroll_delta: 0.0,
+            left_mouse_clicked: false,
             middle_mouse_held: false,
             right_mouse_held: false,
             window_size: (0, 0),
@@ -68,6 +71,9 @@ impl InputState {
                 self.mouse_delta.1 += new_pos.1 - self.mouse_position.1;
                 self.mouse_position = new_pos;
             }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                self.left_mouse_clicked = true;
+            }
             WindowEvent::MouseInput { state, button: MouseButton::Middle, .. } => {
                 self.middle_mouse_held = *state == ElementState::Pressed;
             }
@@ -94,6 +100,7 @@ impl InputState {
         self.keys_just_pressed.clear();
         self.scroll_delta = 0.0;
         self.mouse_delta = (0.0, 0.0);
+        self.left_mouse_clicked = false;
     }
 
     /// True only during the frame the key was first pressed. Use this for
@@ -102,4 +109,9 @@ impl InputState {
         self.keys_just_pressed.contains(&key)
     }
 
+    /// True every frame the key is down (modifiers, continuous actions).
+    pub fn is_key_held(&self, key: KeyCode) -> bool {
+        self.keys_held.contains(&key)
+    }
+
 }
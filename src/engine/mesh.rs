@@ -164,3 +164,106 @@ pub fn triangulate_smooth(poly: &PolyMesh) -> RenderMesh {
 
     RenderMesh { vertices, indices }
 }
+
+/// Convert a subdivided PolyMesh to a RenderMesh with every vertex pushed onto
+/// the Catmull-Clark limit surface.
+///
+/// Same index buffer as `triangulate_smooth()`, but positions and normals come
+/// from `subdivide::limit_vertices()` instead of the control cage. A level-2
+/// sphere evaluated at its limit is visibly rounder than the raw level-2 cage,
+/// and its normals are exact rather than area-weighted approximations.
+pub fn triangulate_limit(poly: &PolyMesh) -> RenderMesh {
+    let mut render_mesh = triangulate_smooth(poly);
+    let limit = super::subdivide::limit_vertices(poly);
+    for (vertex, lv) in render_mesh.vertices.iter_mut().zip(limit.iter()) {
+        vertex.position = lv.position.to_array();
+        vertex.normal   = lv.normal.to_array();
+    }
+    render_mesh
+}
+
+// ============================================================================
+// CONTENT HASH
+// ============================================================================
+
+/// Quantisation step for content hashing: positions and normals are snapped to
+/// a grid of this spacing before hashing, so float noise from reordered
+/// arithmetic usually hashes identically. Values straddling a grid boundary can
+/// still round apart however close they are.
+pub const HASH_QUANTUM: f32 = 1e-4;
+
+/// Stable 64-bit content hash of a mesh.
+///
+/// Independent of vertex order and face order, but sensitive to winding.
+/// Uses FNV-1a rather than `std::hash` so the value is identical across Rust
+/// versions and platforms — it is written to golden files.
+pub trait ContentHash {
+    fn content_hash(&self) -> u64;
+}
+
+impl ContentHash for PolyMesh {
+    fn content_hash(&self) -> u64 {
+        let quantized: Vec<[i64; 3]> = self.positions.iter().map(|&p| quantize(p)).collect();
+        let vertex_hashes = quantized.iter().map(|q| fnv1a(q)).collect();
+        let face_hashes = self.faces.iter()
+            .map(|face| hash_polygon(face.iter().map(|&v| quantized[v]).collect()))
+            .collect();
+        combine_unordered(vertex_hashes, face_hashes)
+    }
+}
+
+impl ContentHash for RenderMesh {
+    fn content_hash(&self) -> u64 {
+        let quantized: Vec<[i64; 6]> = self.vertices.iter().map(|v| {
+            let p = quantize(Vec3::from_array(v.position));
+            let n = quantize(Vec3::from_array(v.normal));
+            [p[0], p[1], p[2], n[0], n[1], n[2]]
+        }).collect();
+        let vertex_hashes = quantized.iter().map(|q| fnv1a(q)).collect();
+        let tri_hashes = self.indices.chunks_exact(3)
+            .map(|tri| hash_polygon(tri.iter().map(|&i| quantized[i as usize]).collect()))
+            .collect();
+        combine_unordered(vertex_hashes, tri_hashes)
+    }
+}
+
+fn quantize(p: Vec3) -> [i64; 3] {
+    let q = (p / HASH_QUANTUM).round();
+    [q.x as i64, q.y as i64, q.z as i64]
+}
+
+/// Hash a polygon's corner keys independently of which corner it starts at:
+/// rotate so the smallest key comes first, keeping cyclic (winding) order.
+fn hash_polygon<K: Ord + Copy + AsRef<[i64]>>(mut corners: Vec<K>) -> u64 {
+    if let Some(start) = (0..corners.len()).min_by_key(|&i| corners[i]) {
+        corners.rotate_left(start);
+    }
+    let flat: Vec<i64> = corners.iter().flat_map(|c| c.as_ref().iter().copied()).collect();
+    fnv1a(&flat)
+}
+
+/// Order-independent combination: sort each hash list, then hash counts + contents.
+fn combine_unordered(mut vertex_hashes: Vec<u64>, mut face_hashes: Vec<u64>) -> u64 {
+    vertex_hashes.sort_unstable();
+    face_hashes.sort_unstable();
+    let mut words: Vec<i64> = Vec::with_capacity(2 + vertex_hashes.len() + face_hashes.len());
+    words.push(vertex_hashes.len() as i64);
+    words.push(face_hashes.len() as i64);
+    words.extend(vertex_hashes.iter().map(|&h| h as i64));
+    words.extend(face_hashes.iter().map(|&h| h as i64));
+    fnv1a(&words)
+}
+
+/// FNV-1a over the little-endian bytes of each word.
+fn fnv1a(words: &[i64]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME:  u64 = 0x0000_0100_0000_01b3;
+    let mut h = OFFSET;
+    for w in words {
+        for b in w.to_le_bytes() {
+            h ^= b as u64;
+            h = h.wrapping_mul(PRIME);
+        }
+    }
+    h
+}
@@ -0,0 +1,140 @@
+// Mesh registry: owns the GPU buffers for every procedural mesh.
+// See docs/research/rendering-architecture.md for the instancing strategy.
+//
+// Meshes are generated once at startup (skin → CC → triangulate), uploaded here,
+// and referenced from ECS entities through a `MeshHandle` component. The
+// renderer groups instances by handle and issues one instanced draw per mesh
+// (per LOD level in use).
+//
+// A handle may carry several LOD levels, finest first. Level i is used from
+// `lod_distance * 2^(i-1)` onward (level 0 below `lod_distance`), shifted by
+// the renderer's LOD bias.
+
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+use super::components::MeshHandle;
+use super::mesh::RenderMesh;
+
+/// Vertex + index buffers for one LOD level of a registered mesh.
+pub struct GpuMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub num_indices: u32,
+}
+
+/// All LOD levels of one registered mesh.
+pub struct MeshEntry {
+    /// Finest first; never empty.
+    pub levels: Vec<GpuMesh>,
+    /// Camera distance at which level 1 takes over (doubling per further level).
+    pub lod_distance: f32,
+    /// Model-space bounding sphere of level 0 (AABB centre, farthest vertex) for culling.
+    pub bounds_center: Vec3,
+    pub bounds_radius: f32,
+}
+
+impl MeshEntry {
+    /// LOD level for an instance `distance` from the camera. Positive `bias`
+    /// switches to coarser levels sooner (each +1 halves the switch distances).
+    pub fn select_lod(&self, distance: f32, bias: f32) -> usize {
+        lod_level(distance, self.lod_distance, bias, self.levels.len() - 1)
+    }
+}
+
+/// Level 0 below `lod_distance`, level i from `lod_distance * 2^(i-1)`, capped
+/// at `coarsest`; `bias` scales the switch distances by `2^-bias`.
+fn lod_level(distance: f32, lod_distance: f32, bias: f32, coarsest: usize) -> usize {
+    if coarsest == 0 { return 0; }
+    let x = distance / lod_distance * bias.exp2();
+    if x < 1.0 { 0 } else { ((x.log2().floor() as usize) + 1).min(coarsest) }
+}
+
+/// Handle → GPU buffers. Handles are dense indices in registration order.
+pub struct MeshRegistry {
+    meshes: Vec<MeshEntry>,
+}
+
+impl MeshRegistry {
+    pub fn new() -> Self {
+        Self { meshes: Vec::new() }
+    }
+
+    /// Upload LOD levels (finest first) to the GPU and return a handle for use
+    /// in `MeshHandle` components. A single level disables LOD switching.
+    pub fn register(&mut self, device: &wgpu::Device, label: &str, lods: &[RenderMesh], lod_distance: f32) -> MeshHandle {
+        assert!(!lods.is_empty(), "mesh {label} registered without geometry");
+        let levels = lods.iter().enumerate().map(|(level, mesh)| {
+            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} LOD{level} Vertex Buffer")),
+                contents: mesh.vertex_bytes(),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} LOD{level} Index Buffer")),
+                contents: mesh.index_bytes(),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+            GpuMesh { vertex_buffer, index_buffer, num_indices: mesh.index_count() as u32 }
+        }).collect();
+
+        let (bounds_center, bounds_radius) = bounding_sphere(&lods[0]);
+
+        let handle = MeshHandle(self.meshes.len() as u32);
+        self.meshes.push(MeshEntry { levels, lod_distance, bounds_center, bounds_radius });
+        handle
+    }
+
+    pub fn get(&self, handle: MeshHandle) -> &MeshEntry {
+        &self.meshes[handle.0 as usize]
+    }
+
+    pub fn mesh_count(&self) -> usize { self.meshes.len() }
+}
+
+/// Sphere around the mesh's AABB centre reaching its farthest vertex.
+fn bounding_sphere(mesh: &RenderMesh) -> (Vec3, f32) {
+    if mesh.vertices.is_empty() {
+        return (Vec3::ZERO, 0.0);
+    }
+    let (min, max) = mesh.vertices.iter().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), v| {
+            let p = Vec3::from(v.position);
+            (min.min(p), max.max(p))
+        },
+    );
+    let center = (min + max) * 0.5;
+    let radius = mesh.vertices.iter()
+        .map(|v| Vec3::from(v.position).distance(center))
+        .fold(0.0, f32::max);
+    (center, radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lod_level;
+
+    #[test]
+    fn lod_switches_at_doubling_distances() {
+        // lod_distance 10: level 1 from 10, level 2 from 20, level 3 from 40.
+        assert_eq!(lod_level(0.0, 10.0, 0.0, 3), 0);
+        assert_eq!(lod_level(9.99, 10.0, 0.0, 3), 0);
+        assert_eq!(lod_level(10.0, 10.0, 0.0, 3), 1);
+        assert_eq!(lod_level(19.99, 10.0, 0.0, 3), 1);
+        assert_eq!(lod_level(20.0, 10.0, 0.0, 3), 2);
+        assert_eq!(lod_level(40.0, 10.0, 0.0, 3), 3);
+        // Never past the coarsest level, and a single level never switches.
+        assert_eq!(lod_level(1000.0, 10.0, 0.0, 3), 3);
+        assert_eq!(lod_level(1000.0, 10.0, 0.0, 0), 0);
+    }
+
+    #[test]
+    fn lod_bias_scales_switch_distances() {
+        // +1 halves the switch distances, -1 doubles them.
+        assert_eq!(lod_level(4.99, 10.0, 1.0, 3), 0);
+        assert_eq!(lod_level(5.0, 10.0, 1.0, 3), 1);
+        assert_eq!(lod_level(10.0, 10.0, 1.0, 3), 2);
+        assert_eq!(lod_level(19.99, 10.0, -1.0, 3), 0);
+        assert_eq!(lod_level(20.0, 10.0, -1.0, 3), 1);
+    }
+}
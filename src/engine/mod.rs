@@ -6,18 +6,32 @@
 pub mod camera;
 pub mod components;
 pub mod debug_overlay;
+pub mod decals;
+pub mod event_log;
+pub mod frustum;
 pub mod input;
 pub mod mesh;
+pub mod mesh_registry;
 pub mod navigation;
 pub mod orca;
+pub mod particles;
+pub mod picking;
+pub mod renderer;
 pub mod skin;
 pub mod subdivide;
 pub mod systems;
+pub mod terrain;
+pub mod weld;
+pub mod world_ui;
+
+#[cfg(test)]
+mod golden;
 
 // Re-export commonly used items
 pub use components::*;
-pub use mesh::triangulate_smooth;
+pub use mesh::{triangulate_smooth, triangulate_limit};
 pub use navigation::{NavigationGrid, compute_astar, GRID_WIDTH, GRID_HEIGHT};
 pub use orca::{AgentSnapshot, SpatialGrid, compute_orca_velocity};
 pub use skin::{SkinGraph, skin_modifier};
 pub use subdivide::subdivide;
+pub use weld::weld;
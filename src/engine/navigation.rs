@@ -1,7 +1,7 @@
 // Flowfield pathfinding for RTS group movement.
 // See docs/research/pathfinding.md for architecture decisions.
 //
-// Layer 1: NavigationGrid — static walkability per tile.
+// Layer 1: NavigationGrid — static walkability and terrain cost per tile.
 // Layer 2: FlowField — Dijkstra integration field + gradient directions.
 //          Sprint 3: density-feedback surcharge spreads units across corridors.
 // Layer 2a: DensityMap — per-cell unit count, rebuilt every N frames,
@@ -16,32 +16,36 @@ use std::cmp::Reverse;
 // ============================================================================
 
 /// Number of grid cells along X.
-pub const GRID_WIDTH: u32 = 100;
+pub const GRID_WIDTH: u32 = 128;
 /// Number of grid cells along Z.
-pub const GRID_HEIGHT: u32 = 100;
+pub const GRID_HEIGHT: u32 = 128;
 /// World units per cell. Cell (cx, cz) covers [cx - WORLD_HALF .. cx+1 - WORLD_HALF].
 pub const CELL_SIZE: f32 = 1.0;
 /// World runs from -WORLD_HALF to +WORLD_HALF on both X and Z.
-pub const WORLD_HALF: f32 = 50.0;
+pub const WORLD_HALF: f32 = 64.0;
 
 // ============================================================================
 // NAVIGATION GRID
 // ============================================================================
 
-/// Static tile walkability. Rebuilt when buildings are placed or destroyed.
+/// Static tile walkability and cost. Rebuilt when buildings are placed or destroyed.
 /// Dynamic obstacles (units) are handled by ORCA (Layer 3), not baked here.
 pub struct NavigationGrid {
     /// True if units can walk through this cell.
     pub walkable: Vec<bool>,
+    /// Cost multiplier for entering this cell (1.0 = open ground, always ≥ 1.0).
+    pub cost: Vec<f32>,
     pub width: u32,
     pub height: u32,
 }
 
 impl NavigationGrid {
-    /// Fully open grid — all cells walkable. Used until terrain is introduced.
+    /// Fully open grid — all cells walkable at cost 1.0. `Terrain::bake_navigation`
+    /// then closes cliff tops and writes terrain costs.
     pub fn new_open(width: u32, height: u32) -> Self {
         Self {
             walkable: vec![true; (width * height) as usize],
+            cost: vec![1.0; (width * height) as usize],
             width,
             height,
         }
@@ -193,8 +197,9 @@ impl FlowField {
 
 /// Compute a flowfield for the given goal world position using uniform-cost BFS.
 ///
-/// All walkable cells have cost 1.0. Use `compute_flowfield_with_density` for
-/// the Sprint 3 density-feedback variant that spreads units across corridors.
+/// All walkable cells have cost 1.0 (terrain costs are ignored). Use
+/// `compute_flowfield_with_density` for the Sprint 3 density-feedback variant
+/// that spreads units across corridors and honours terrain costs.
 pub fn compute_flowfield(grid: &NavigationGrid, goal_world: Vec3) -> FlowField {
     let goal_cell = grid.world_to_cell_clamped(goal_world);
     let size = (grid.width * grid.height) as usize;
@@ -237,7 +242,7 @@ pub fn compute_flowfield(grid: &NavigationGrid, goal_world: Vec3) -> FlowField {
 /// water finding all downhill paths simultaneously.
 ///
 /// `density_weight` controls aggressiveness. The research doc recommends
-/// starting at 0.3–0.5 × terrain_cost (i.e. 0.3–0.5 for our uniform grid).
+/// starting at 0.3–0.5 × terrain_cost (i.e. 0.3–0.5 on open ground).
 /// Too high → units take very roundabout routes. Too low → corridor pileups.
 ///
 /// See pathfinding.md §"The Novel Part: Density Feedback Cost".
@@ -282,8 +287,7 @@ pub fn compute_flowfield_with_density(
             }
 
             // Cost to enter neighbor = terrain_cost + density surcharge.
-            // terrain_cost = 1.0 (uniform grid; Sprint 4+ may add terrain types).
-            let step_cost = 1.0_f32 + density_weight * density.get(nb);
+            let step_cost = grid.cost[ni] + density_weight * density.get(nb);
             let new_cost = pos_cost + step_cost;
 
             if new_cost < integration[ni] {
@@ -458,7 +462,7 @@ pub fn compute_astar(nav: &NavigationGrid, start: Vec3, goal: Vec3) -> Vec<Vec2>
             let nb      = UVec2::new(nx as u32, ny as u32);
             let nb_idx  = flat(nb);
             if closed[nb_idx] || !nav.walkable[nb_idx] { continue; }
-            let new_g = cur_g + cost;
+            let new_g = cur_g + cost * nav.cost[nb_idx];
             if new_g < g_score[nb_idx] {
                 g_score[nb_idx]   = new_g;
                 came_from[nb_idx] = cur as u32;
@@ -538,8 +542,11 @@ fn string_pull(nav: &NavigationGrid, cells: &[UVec2]) -> Vec<UVec2> {
     out
 }
 
-/// Bresenham line-of-sight: true if every cell between `a` and `b` is walkable.
+/// Bresenham line-of-sight: true if every cell between `a` and `b` is walkable
+/// and no costlier than the dearer endpoint, so string-pulling never shortcuts
+/// a path across terrain A* routed around.
 fn has_los(nav: &NavigationGrid, a: UVec2, b: UVec2) -> bool {
+    let max_cost = nav.cost[(a.y * nav.width + a.x) as usize].max(nav.cost[(b.y * nav.width + b.x) as usize]);
     let mut x0 = a.x as i32;
     let mut y0 = a.y as i32;
     let x1 = b.x as i32;
@@ -550,7 +557,8 @@ fn has_los(nav: &NavigationGrid, a: UVec2, b: UVec2) -> bool {
     let sy = if y0 < y1 { 1i32 } else { -1 };
     let mut err = dx + dy;
     loop {
-        if !nav.walkable[(y0 as u32 * nav.width + x0 as u32) as usize] { return false; }
+        let i = (y0 as u32 * nav.width + x0 as u32) as usize;
+        if !nav.walkable[i] || nav.cost[i] > max_cost { return false; }
         if x0 == x1 && y0 == y1 { break; }
         let e2 = 2 * err;
         if e2 >= dy { err += dy; x0 += sx; }
@@ -0,0 +1,308 @@
+// Particle effects: CPU simulation of short-lived billboards.
+// See docs/research/rendering-architecture.md §"Particles".
+//
+// A `ParticleEffect` describes what to emit (rate, lifetime, velocity, color
+// and size over life); a `ParticleEmitter` component attaches one to any
+// entity with a `Transform`. `ParticleSystem::update` spawns from every
+// emitter and integrates all live particles; `submit` hands them to the
+// renderer as camera-facing quads.
+//
+// Each emitter owns an RNG seeded at construction, so the same seeds, spawn
+// order and frame times reproduce the same particles. Particles outlive their
+// emitter: despawning an entity mid-explosion lets the debris finish.
+
+use std::sync::Arc;
+use bevy_ecs::prelude::*;
+use glam::Vec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use super::components::Transform;
+use super::renderer::{ParticleBlend, ParticleInstance, Renderer};
+
+/// Live particle cap. Emitters stop spawning until older particles expire.
+pub const MAX_PARTICLES: usize = 20_000;
+
+/// Values a `Curve` can interpolate.
+pub trait Lerp: Copy {
+    fn lerp(a: Self, b: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(a: Self, b: Self, t: f32) -> Self { a + (b - a) * t }
+}
+
+impl Lerp for [f32; 4] {
+    fn lerp(a: Self, b: Self, t: f32) -> Self {
+        std::array::from_fn(|i| f32::lerp(a[i], b[i], t))
+    }
+}
+
+/// Piecewise-linear curve over normalized particle age (0 = birth, 1 = death).
+/// Clamps to the first/last key outside their range.
+#[derive(Debug, Clone)]
+pub struct Curve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T: Lerp> Curve<T> {
+    /// `keys` are (age, value) pairs sorted by age; at least one is required.
+    pub fn new(keys: Vec<(f32, T)>) -> Self {
+        assert!(!keys.is_empty(), "curve needs at least one key");
+        assert!(keys.windows(2).all(|w| w[0].0 <= w[1].0), "curve keys must be sorted by age");
+        Self { keys }
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self { keys: vec![(0.0, value)] }
+    }
+
+    /// Straight line from `start` at birth to `end` at death.
+    pub fn linear(start: T, end: T) -> Self {
+        Self { keys: vec![(0.0, start), (1.0, end)] }
+    }
+
+    pub fn sample(&self, t: f32) -> T {
+        let next = self.keys.partition_point(|(age, _)| *age <= t);
+        match (next.checked_sub(1).map(|i| self.keys[i]), self.keys.get(next)) {
+            (Some((a_age, a)), Some(&(b_age, b))) => T::lerp(a, b, (t - a_age) / (b_age - a_age)),
+            (Some((_, a)), None) => a,
+            (None, Some(&(_, b))) => b,
+            (None, None) => unreachable!("curve has at least one key"),
+        }
+    }
+}
+
+/// What an emitter spawns. Shared between emitters via `Arc`.
+#[derive(Debug, Clone)]
+pub struct ParticleEffect {
+    pub blend: ParticleBlend,
+    /// Continuous emission in particles per second.
+    pub rate: f32,
+    /// Particles emitted at once on the emitter's first update (explosions, muzzle flashes).
+    pub burst: u32,
+    /// Lifetime in seconds, uniformly random in [min, max].
+    pub lifetime: (f32, f32),
+    /// Particles spawn uniformly inside this sphere around the emitter.
+    pub spawn_radius: f32,
+    /// Initial velocity in the emitter's model space (follows its rotation).
+    pub velocity: Vec3,
+    /// Random extra velocity, uniform in a ball of this radius.
+    pub velocity_spread: f32,
+    /// World-space acceleration: gravity for debris, upward buoyancy for smoke.
+    pub acceleration: Vec3,
+    /// Fraction of velocity lost per second.
+    pub drag: f32,
+    /// Linear HDR color over life; alpha fades the sprite.
+    pub color: Curve<[f32; 4]>,
+    /// Quad edge length in world units over life.
+    pub size: Curve<f32>,
+}
+
+/// Attaches a particle effect to an entity with a `Transform`.
+#[derive(Component, Debug, Clone)]
+pub struct ParticleEmitter {
+    pub effect: Arc<ParticleEffect>,
+    /// Spawn point in the entity's model space.
+    pub offset: Vec3,
+    /// Paused emitters spawn nothing; their live particles still finish.
+    pub enabled: bool,
+    rng: StdRng,
+    /// Fractional particles carried between frames so low rates still emit.
+    spawn_debt: f32,
+    burst_pending: bool,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: Arc<ParticleEffect>, offset: Vec3, seed: u64) -> Self {
+        Self {
+            effect,
+            offset,
+            enabled: true,
+            rng: StdRng::seed_from_u64(seed),
+            spawn_debt: 0.0,
+            burst_pending: true,
+        }
+    }
+}
+
+struct Particle {
+    effect: Arc<ParticleEffect>,
+    position: Vec3,
+    velocity: Vec3,
+    age: f32,
+    lifetime: f32,
+}
+
+/// All live particles, independent of the entities that emitted them.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self { particles: Vec::new() }
+    }
+
+    pub fn particle_count(&self) -> usize { self.particles.len() }
+
+    /// Advance live particles by `dt`, retire expired ones, then spawn from
+    /// every enabled emitter.
+    pub fn update(&mut self, world: &mut World, dt: f32) {
+        self.particles.retain_mut(|p| {
+            p.age += dt;
+            if p.age >= p.lifetime {
+                return false;
+            }
+            p.velocity += p.effect.acceleration * dt;
+            p.velocity *= (1.0 - p.effect.drag * dt).max(0.0);
+            p.position += p.velocity * dt;
+            true
+        });
+
+        let mut query = world.query::<(&Transform, &mut ParticleEmitter)>();
+        for (transform, mut emitter) in query.iter_mut(world) {
+            if !emitter.enabled {
+                continue;
+            }
+            let emitter = &mut *emitter;
+            emitter.spawn_debt += emitter.effect.rate * dt;
+            let mut count = emitter.spawn_debt.floor();
+            emitter.spawn_debt -= count;
+            if std::mem::take(&mut emitter.burst_pending) {
+                count += emitter.effect.burst as f32;
+            }
+
+            let origin = transform.matrix().transform_point3(emitter.offset);
+            for _ in 0..count as u32 {
+                if self.particles.len() >= MAX_PARTICLES {
+                    break;
+                }
+                let effect = &emitter.effect;
+                let rng = &mut emitter.rng;
+                let (min_life, max_life) = effect.lifetime;
+                self.particles.push(Particle {
+                    position: origin + random_in_ball(rng) * effect.spawn_radius,
+                    velocity: transform.rotation * effect.velocity + random_in_ball(rng) * effect.velocity_spread,
+                    age: 0.0,
+                    lifetime: rng.gen_range(min_life..=max_life),
+                    effect: Arc::clone(effect),
+                });
+            }
+        }
+    }
+
+    /// Queue every live particle as a billboard for the next render().
+    pub fn submit(&self, renderer: &mut Renderer) {
+        for p in &self.particles {
+            let t = p.age / p.lifetime;
+            renderer.submit_particle(p.effect.blend, ParticleInstance {
+                position: p.position.to_array(),
+                size: p.effect.size.sample(t),
+                color: p.effect.color.sample(t),
+            });
+        }
+    }
+}
+
+/// Uniform point in the unit ball (rejection sampling).
+fn random_in_ball(rng: &mut StdRng) -> Vec3 {
+    loop {
+        let v = Vec3::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0));
+        if v.length_squared() <= 1.0 {
+            return v;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Long-lived, randomly spread particles so every RNG draw shows up.
+    fn effect(rate: f32, burst: u32) -> Arc<ParticleEffect> {
+        Arc::new(ParticleEffect {
+            blend: ParticleBlend::Additive,
+            rate,
+            burst,
+            lifetime: (50.0, 100.0),
+            spawn_radius: 0.5,
+            velocity: Vec3::Y,
+            velocity_spread: 1.0,
+            acceleration: Vec3::new(0.0, -9.8, 0.0),
+            drag: 0.1,
+            color: Curve::constant([1.0; 4]),
+            size: Curve::constant(0.2),
+        })
+    }
+
+    fn world_with(emitters: impl IntoIterator<Item = ParticleEmitter>) -> World {
+        let mut world = World::new();
+        for emitter in emitters {
+            world.spawn((Transform::from_position(Vec3::new(1.0, 2.0, 3.0)), emitter));
+        }
+        world
+    }
+
+    /// Counts of particles spawned by each of `steps` updates.
+    fn spawned_per_update(world: &mut World, steps: usize, dt: f32) -> Vec<usize> {
+        let mut system = ParticleSystem::new();
+        (0..steps).map(|_| {
+            let before = system.particle_count();
+            system.update(world, dt);
+            system.particle_count() - before
+        }).collect()
+    }
+
+    #[test]
+    fn same_seeds_reproduce_the_same_particles() {
+        let run = || {
+            let mut world = world_with([
+                ParticleEmitter::new(effect(30.0, 10), Vec3::ZERO, 7),
+                ParticleEmitter::new(effect(12.5, 0), Vec3::X, 8),
+            ]);
+            let mut system = ParticleSystem::new();
+            for _ in 0..10 {
+                system.update(&mut world, 1.0 / 60.0);
+            }
+            system.particles.iter()
+                .map(|p| (p.position, p.velocity, p.age, p.lifetime))
+                .collect::<Vec<_>>()
+        };
+        let (a, b) = (run(), run());
+        assert!(!a.is_empty());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_seeds_diverge() {
+        let positions = |seed| {
+            let mut world = world_with([ParticleEmitter::new(effect(0.0, 4), Vec3::ZERO, seed)]);
+            let mut system = ParticleSystem::new();
+            system.update(&mut world, 0.1);
+            system.particles.iter().map(|p| p.position).collect::<Vec<_>>()
+        };
+        assert_ne!(positions(1), positions(2));
+    }
+
+    #[test]
+    fn burst_fires_only_on_the_first_update() {
+        let mut world = world_with([ParticleEmitter::new(effect(0.0, 5), Vec3::ZERO, 1)]);
+        assert_eq!(spawned_per_update(&mut world, 4, 0.1), [5, 0, 0, 0]);
+    }
+
+    #[test]
+    fn fractional_rate_carries_spawn_debt() {
+        let mut world = world_with([ParticleEmitter::new(effect(0.5, 0), Vec3::ZERO, 1)]);
+        assert_eq!(spawned_per_update(&mut world, 6, 1.0), [0, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn curve_clamps_outside_its_keys() {
+        let curve = Curve::new(vec![(0.25, 2.0), (0.75, 4.0)]);
+        assert_eq!(curve.sample(0.0), 2.0);
+        assert_eq!(curve.sample(-1.0), 2.0);
+        assert_eq!(curve.sample(0.5), 3.0);
+        assert_eq!(curve.sample(1.0), 4.0);
+        assert_eq!(curve.sample(2.0), 4.0);
+    }
+}
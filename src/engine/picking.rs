@@ -0,0 +1,102 @@
+// Screen-to-world picking: rays from the camera through a pixel, and what
+// they hit first. `RtsCamera::screen_ray` builds the ray; `Terrain::raycast`
+// finds the ground under the cursor; `pick_nearest` tests bounding spheres.
+//
+// Entity picking is only as precise as the spheres, so callers clip it to the
+// ground hit: a unit behind a ridge is not picked through the ridge.
+
+use glam::Vec3;
+
+/// Half-line from `origin` along unit-length `direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// `direction` need not be normalized.
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction: direction.normalize() }
+    }
+
+    /// Point at distance `t` along the ray.
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Distance to the horizontal plane y = `height`, if the ray reaches it.
+    pub fn intersect_plane(&self, height: f32) -> Option<f32> {
+        if self.direction.y.abs() < f32::EPSILON {
+            return None;
+        }
+        let t = (height - self.origin.y) / self.direction.y;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Distance to the first point of the sphere the ray touches, or 0 when
+    /// the origin is inside it.
+    pub fn intersect_sphere(&self, center: Vec3, radius: f32) -> Option<f32> {
+        let to_center = center - self.origin;
+        let along = to_center.dot(self.direction);
+        let miss_sq = to_center.length_squared() - along * along;
+        let radius_sq = radius * radius;
+        if miss_sq > radius_sq {
+            return None;
+        }
+        let half_chord = (radius_sq - miss_sq).sqrt();
+        let (near, far) = (along - half_chord, along + half_chord);
+        if far < 0.0 {
+            None
+        } else {
+            Some(near.max(0.0))
+        }
+    }
+}
+
+/// The candidate whose bounding sphere (center, radius) the ray enters first,
+/// ignoring hits farther than `max_distance`. Returns it with its hit distance.
+pub fn pick_nearest<T>(ray: &Ray, candidates: impl IntoIterator<Item = (T, Vec3, f32)>, max_distance: f32) -> Option<(T, f32)> {
+    candidates
+        .into_iter()
+        .filter_map(|(item, center, radius)| {
+            let t = ray.intersect_sphere(center, radius)?;
+            (t <= max_distance).then_some((item, t))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersect_sphere_returns_entry_distance() {
+        let ray = Ray::new(Vec3::ZERO, Vec3::new(0.0, 0.0, -2.0));
+        assert_eq!(ray.intersect_sphere(Vec3::new(0.0, 0.0, -10.0), 2.0), Some(8.0));
+        // Grazing hit: the ray touches the sphere's side.
+        let t = ray.intersect_sphere(Vec3::new(1.0, 0.0, -5.0), 1.0).unwrap();
+        assert!((t - 5.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn intersect_sphere_misses_beside_and_behind() {
+        let ray = Ray::new(Vec3::ZERO, Vec3::NEG_Z);
+        assert_eq!(ray.intersect_sphere(Vec3::new(3.0, 0.0, -10.0), 2.0), None);
+        assert_eq!(ray.intersect_sphere(Vec3::new(0.0, 0.0, 10.0), 2.0), None);
+    }
+
+    #[test]
+    fn intersect_sphere_from_inside_is_zero() {
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 0.5), Vec3::NEG_Z);
+        assert_eq!(ray.intersect_sphere(Vec3::ZERO, 1.0), Some(0.0));
+    }
+
+    #[test]
+    fn pick_nearest_prefers_first_entry_within_range() {
+        let ray = Ray::new(Vec3::ZERO, Vec3::X);
+        let spheres = [("far", Vec3::new(10.0, 0.0, 0.0), 1.0), ("near", Vec3::new(5.0, 0.0, 0.0), 1.0)];
+        assert_eq!(pick_nearest(&ray, spheres, 100.0), Some(("near", 4.0)));
+        assert_eq!(pick_nearest(&ray, spheres, 3.0), None);
+    }
+}
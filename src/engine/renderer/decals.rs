@@ -0,0 +1,184 @@
+// Ground decals: placement footprints, rally marks and scorch marks.
+// See docs/research/rendering-architecture.md §"Decals".
+//
+// Decals arrive already draped over the terrain (engine/decals.rs lays a
+// grid of vertices on the surface), so drawing them is one alpha-blended,
+// depth-tested triangle list after the opaque pass: units and cliffs in
+// front hide them, and they never write depth. The pattern of each shape is
+// procedural, drawn from the decal-local coordinates carried per vertex.
+
+use glam::{Vec2, Vec3};
+use super::growable_buffer::GrowableBuffer;
+use super::post::HDR_FORMAT;
+
+/// Procedural pattern of a decal (mirrored in shader_decals.wgsl).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecalShape {
+    /// Building footprint: tinted fill with a bright border and tile grid.
+    Footprint,
+    /// Rally point: ring around a center dot.
+    Rally,
+    /// Blast mark: dark blotch with a ragged edge.
+    Scorch,
+}
+
+/// GPU layout of one decal vertex (48 bytes).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct DecalVertex {
+    position: [f32; 3],
+    shape: u32,
+    /// Offset from the decal center in world units, along the decal's X/Z axes.
+    local: [f32; 2],
+    half_size: [f32; 2],
+    color: [f32; 4],
+}
+
+impl DecalVertex {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const COLUMN: wgpu::BufferAddress = std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress;
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<DecalVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: 12,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Uint32,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 2,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Pipeline and vertex buffer for all decals.
+pub struct DecalPass {
+    shader: wgpu::ShaderModule,
+    layout: wgpu::PipelineLayout,
+    pipeline: wgpu::RenderPipeline,
+    buffer: GrowableBuffer,
+    /// Submitted since the last upload.
+    vertices: Vec<DecalVertex>,
+    /// Vertex count of the last upload.
+    vertex_count: u32,
+}
+
+impl DecalPass {
+    /// `camera_layout` is the scene's group-0 layout (view-projection uniforms).
+    pub fn new(device: &wgpu::Device, camera_layout: &wgpu::BindGroupLayout, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Decal Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_decals.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Decal Pipeline Layout"),
+            bind_group_layouts: &[camera_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = create_pipeline(device, &layout, &shader, sample_count);
+        let buffer = GrowableBuffer::new(
+            device,
+            "Decal Buffer",
+            wgpu::BufferUsages::VERTEX,
+            (1024 * std::mem::size_of::<DecalVertex>()) as u64,
+        );
+        Self { shader, layout, pipeline, buffer, vertices: Vec::new(), vertex_count: 0 }
+    }
+
+    /// Rebuild the pipeline for a new MSAA sample count.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline = create_pipeline(device, &self.layout, &self.shader, sample_count);
+    }
+
+    /// Queue a triangle list of (world position, decal-local offset) vertices.
+    pub fn submit(&mut self, shape: DecalShape, half_size: Vec2, color: [f32; 4], triangles: &[(Vec3, Vec2)]) {
+        self.vertices.extend(triangles.iter().map(|(position, local)| DecalVertex {
+            position: position.to_array(),
+            shape: shape as u32,
+            local: local.to_array(),
+            half_size: half_size.to_array(),
+            color,
+        }));
+    }
+
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.vertex_count = self.vertices.len() as u32;
+        self.buffer.write(device, queue, bytemuck::cast_slice(&self.vertices));
+        self.vertices.clear();
+    }
+
+    /// Draw the last upload. Group 0 (camera uniforms) must already be bound.
+    /// Returns the number of draw calls issued.
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) -> u32 {
+        if self.vertex_count == 0 {
+            return 0;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_vertex_buffer(0, self.buffer.buffer().slice(..));
+        pass.draw(0..self.vertex_count, 0..1);
+        1
+    }
+}
+
+/// Alpha-blended, depth-tested, no depth writes.
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Decal Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_decal"),
+            buffers: &[DecalVertex::desc()],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs_decal"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: HDR_FORMAT,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,60 @@
+// GPU buffer that reallocates with headroom when a write exceeds its capacity.
+//
+// Used for per-frame data whose size follows the entity count (instances,
+// lights, particles). Growth is geometric (next power of two), so a steadily
+// growing scene reallocates O(log n) times rather than every frame.
+
+/// A `wgpu::Buffer` plus capacity bookkeeping. Always has `COPY_DST` usage.
+pub struct GrowableBuffer {
+    buffer: wgpu::Buffer,
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    /// Number of times the buffer has been reallocated since creation.
+    reallocations: u32,
+}
+
+impl GrowableBuffer {
+    /// Headroom floor: never allocate less than this many bytes.
+    const MIN_SIZE: u64 = 256;
+
+    pub fn new(device: &wgpu::Device, label: &'static str, usage: wgpu::BufferUsages, initial_size: u64) -> Self {
+        let usage = usage | wgpu::BufferUsages::COPY_DST;
+        Self {
+            buffer: Self::allocate(device, label, usage, initial_size.max(Self::MIN_SIZE)),
+            label,
+            usage,
+            reallocations: 0,
+        }
+    }
+
+    fn allocate(device: &wgpu::Device, label: &'static str, usage: wgpu::BufferUsages, size: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Upload `data` at offset 0, reallocating first if it does not fit.
+    /// Returns `true` if the buffer was reallocated — bind groups that
+    /// reference it must then be recreated.
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[u8]) -> bool {
+        let needed = data.len() as u64;
+        let grew = needed > self.buffer.size();
+        if grew {
+            let size = needed.next_power_of_two().max(Self::MIN_SIZE);
+            self.buffer = Self::allocate(device, self.label, self.usage, size);
+            self.reallocations += 1;
+        }
+        if !data.is_empty() {
+            queue.write_buffer(&self.buffer, 0, data);
+        }
+        grew
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer { &self.buffer }
+    /// Allocated size in bytes.
+    pub fn size(&self) -> u64 { self.buffer.size() }
+    pub fn reallocations(&self) -> u32 { self.reallocations }
+}
@@ -0,0 +1,149 @@
+// Materials: surface parameters shared by many instances.
+// See docs/research/lighting-implementation.md §"Materials".
+//
+// Every registered material lives in one storage buffer indexed by
+// `MaterialHandle`; instances carry the index, so switching material never
+// breaks a mesh batch. Transparent materials are drawn in a separate,
+// back-to-front sorted pass after all opaque geometry.
+
+use super::super::components::MaterialHandle;
+use super::growable_buffer::GrowableBuffer;
+
+/// Surface description used by the scene shader.
+#[derive(Debug, Clone, Copy)]
+pub struct Material {
+    /// Multiplied with the per-instance color. Alpha < 1 only has an effect
+    /// when `transparent` is set.
+    pub base_color: [f32; 4],
+    /// Blinn-Phong specular strength.
+    pub specular: f32,
+    /// Blinn-Phong exponent: higher = tighter highlight.
+    pub shininess: f32,
+    /// Self-illumination as a multiple of the surface color.
+    pub emissive: f32,
+    /// Alpha-blended, depth-tested without depth writes, sorted back to front.
+    /// Transparent instances do not cast shadows.
+    pub transparent: bool,
+    /// Blend the terrain splat layers (`Renderer::set_splat_map`) over the
+    /// surface by world X/Z. For ground meshes.
+    pub splat: bool,
+    /// Cel shading: lighting quantized into a few flat bands with a hard-edged
+    /// highlight and shadow, instead of smooth Blinn-Phong.
+    pub toon: bool,
+    /// Inverted-hull outline thickness in world units; 0 draws no outline.
+    /// Opaque materials only.
+    pub outline_width: f32,
+    /// Unlit outline color (linear RGB).
+    pub outline_color: [f32; 3],
+}
+
+impl Default for Material {
+    /// Matches the lighting the scene shader used before materials existed.
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            specular: 0.5,
+            shininess: 32.0,
+            emissive: 0.0,
+            transparent: false,
+            splat: false,
+            toon: false,
+            outline_width: 0.0,
+            outline_color: [0.0; 3],
+        }
+    }
+}
+
+/// GPU layout of one material (std430: 48 bytes).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct MaterialGpu {
+    base_color: [f32; 4],
+    specular: f32,
+    shininess: f32,
+    emissive: f32,
+    flags: u32,
+    /// RGB color, A width.
+    outline: [f32; 4],
+}
+
+/// `MaterialGpu::flags` bits (mirrored in shader_instanced.wgsl).
+const FLAG_SPLAT: u32 = 1;
+const FLAG_TOON: u32 = 2;
+
+impl From<&Material> for MaterialGpu {
+    fn from(m: &Material) -> Self {
+        Self {
+            base_color: m.base_color,
+            specular: m.specular,
+            shininess: m.shininess,
+            emissive: m.emissive,
+            flags: if m.splat { FLAG_SPLAT } else { 0 } | if m.toon { FLAG_TOON } else { 0 },
+            outline: [m.outline_color[0], m.outline_color[1], m.outline_color[2], m.outline_width],
+        }
+    }
+}
+
+/// CPU copies of all materials plus their storage buffer and bind group.
+pub struct MaterialLibrary {
+    materials: Vec<Material>,
+    buffer: GrowableBuffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+}
+
+impl MaterialLibrary {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = GrowableBuffer::new(
+            device,
+            "Material Buffer",
+            wgpu::BufferUsages::STORAGE,
+            (16 * std::mem::size_of::<MaterialGpu>()) as u64,
+        );
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                // The outline pass reads widths in the vertex stage.
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("material_bind_group_layout"),
+        });
+        let bind_group = Self::create_bind_group(device, &bind_group_layout, &buffer);
+        Self { materials: Vec::new(), buffer, bind_group_layout, bind_group }
+    }
+
+    fn create_bind_group(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, buffer: &GrowableBuffer) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.buffer().as_entire_binding(),
+            }],
+            label: Some("material_bind_group"),
+        })
+    }
+
+    /// Add a material and re-upload the table.
+    pub fn register(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, material: Material) -> MaterialHandle {
+        let handle = MaterialHandle(self.materials.len() as u32);
+        self.materials.push(material);
+        let gpu: Vec<MaterialGpu> = self.materials.iter().map(MaterialGpu::from).collect();
+        if self.buffer.write(device, queue, bytemuck::cast_slice(&gpu)) {
+            self.bind_group = Self::create_bind_group(device, &self.bind_group_layout, &self.buffer);
+        }
+        handle
+    }
+
+    pub fn get(&self, handle: MaterialHandle) -> &Material {
+        &self.materials[handle.0 as usize]
+    }
+
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout { &self.bind_group_layout }
+    pub fn bind_group(&self) -> &wgpu::BindGroup { &self.bind_group }
+}
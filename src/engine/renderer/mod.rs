@@ -0,0 +1,1157 @@
+// Renderer: owns the wgpu device, surface, pipelines and per-frame GPU buffers.
+// See docs/research/rendering-architecture.md for the instancing strategy.
+//
+// The game drives it once per frame:
+//   renderer.set_camera(view_proj, eye)        — camera uniforms
+//   renderer.set_light(light)                  — directional light (optional, casts shadows)
+//   renderer.set_settings(settings)            — quality options (MSAA, shadows, LOD bias, post)
+//   renderer.submit(mesh, material, model, color, emissive) — one call per instance
+//   renderer.submit_pickable(..., pick_id)     — same, plus an ID for GPU picking
+//   renderer.submit_point_light(light)         — dynamic lights (capped per frame)
+//   renderer.set_splat_map(map)                — terrain layers (once per map; splat.rs)
+//   renderer.set_terrain_height(max)           — ground height range for shadow fitting
+//   renderer.submit_decal(shape, ...)          — ground decals (see decals.rs)
+//   renderer.submit_particle(blend, particle)  — billboards (see particles.rs)
+//   renderer.submit_selection_ring / submit_status_bar — world-space UI (world_ui.rs)
+//   renderer.request_pick(pixel) / last_pick() — async ID-buffer picking (picking.rs)
+//   renderer.render(|frame| { ...overlays... }) — draw + present
+//
+// `Renderer::new_headless()` + `render_to_image()` draw the same frame into an
+// offscreen texture and read it back (headless.rs), for screenshots and
+// image-diff tests without a window.
+//
+// Meshes and materials are registered once at startup with `register_mesh()` /
+// `register_material()`, which return handles for use as ECS components.
+// Opaque instances are grouped by mesh (front to back within each group) and
+// drawn with one instanced draw call per mesh and LOD level, first into the
+// shadow map (see shadow.rs) and then into the main pass, followed by
+// inverted-hull outlines for outlined materials and ground decals. Transparent
+// instances follow in a back-to-front pass, then particle billboards and
+// world-space UI. Instances whose bounding sphere misses both the camera and
+// light frustums are culled on the CPU before upload. When a pick is pending,
+// the visible opaque batches are drawn once more into an ID buffer. The scene
+// renders into an HDR target that post.rs blooms, tone-maps and grades onto
+// the surface.
+
+use std::sync::Arc;
+use glam::{Mat4, Vec2, Vec3};
+use wgpu::util::DeviceExt;
+use winit::window::Window;
+use super::components::{MaterialHandle, MeshHandle};
+use super::frustum::Frustum;
+use super::mesh::{GpuVertex, RenderMesh};
+use super::mesh_registry::MeshRegistry;
+
+mod decals;
+mod growable_buffer;
+mod headless;
+mod material;
+mod particles;
+mod picking;
+mod post;
+mod shadow;
+mod splat;
+mod world_ui;
+use decals::DecalPass;
+use growable_buffer::GrowableBuffer;
+use material::MaterialLibrary;
+use particles::ParticlePass;
+use picking::IdBuffer;
+use post::{PostProcess, HDR_FORMAT};
+use shadow::ShadowMap;
+use splat::TerrainSplat;
+use world_ui::WorldUiPass;
+
+pub use decals::DecalShape;
+pub use headless::Image;
+pub use material::Material;
+pub use particles::{ParticleBlend, ParticleInstance};
+pub use picking::PickResult;
+pub use splat::SplatMap;
+
+// ============================================================================
+// INSTANCE DATA (per-entity, passed alongside its registered mesh)
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InstanceData {
+    /// Column-major model matrix (scale → rotate → translate).
+    pub model: [[f32; 4]; 4],
+    pub color: [f32; 4],
+    /// Self-illumination added on top of lighting, as a multiple of `color`.
+    /// Adds to the material's own emissive.
+    pub emissive: f32,
+    /// Index into the material table (`MaterialHandle`).
+    pub material: u32,
+    /// 64-bit ID written to the pick buffer as (low, high) words; 0 = not pickable.
+    pub pick_id: [u32; 2],
+}
+
+impl InstanceData {
+    pub fn new(model: Mat4, material: MaterialHandle, color: [f32; 4], emissive: f32) -> Self {
+        Self { model: model.to_cols_array_2d(), color, emissive, material: material.0, pick_id: [0; 2] }
+    }
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const COLUMN: wgpu::BufferAddress = std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress;
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                // Model matrix columns (locations 2–5, after vertex position and normal)
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 2,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 3,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                // Color (location 6)
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 4,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                // Emissive strength (location 7)
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 5,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                // Material index (location 8)
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 5 + 4,
+                    shader_location: 8,
+                    format: wgpu::VertexFormat::Uint32,
+                },
+                // Pick ID (location 9, read by the ID pass only)
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 5 + 8,
+                    shader_location: 9,
+                    format: wgpu::VertexFormat::Uint32x2,
+                },
+            ],
+        }
+    }
+}
+
+// ============================================================================
+// UNIFORM DATA (camera and lighting)
+// ============================================================================
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Uniforms {
+    view_proj: [[f32; 4]; 4],
+    camera_pos: [f32; 3],
+    /// Number of valid entries in the point light buffer this frame.
+    point_light_count: u32,
+}
+
+impl Uniforms {
+    fn new() -> Self {
+        Self {
+            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            camera_pos: [0.0, 0.0, 0.0],
+            point_light_count: 0,
+        }
+    }
+}
+
+/// Directional light. `direction` points from the light toward the scene.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightUniform {
+    pub direction: [f32; 3],
+    pub _padding: u32,
+    pub color: [f32; 3],
+    pub _padding2: u32,
+    /// Shadow-map view-projection. Refitted by the renderer every frame.
+    pub view_proj: [[f32; 4]; 4],
+}
+
+impl LightUniform {
+    pub fn new() -> Self {
+        Self {
+            direction: [-0.3, -0.5, -0.6],
+            _padding: 0,
+            color: [1.0, 1.0, 1.0],
+            _padding2: 0,
+            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+        }
+    }
+}
+
+/// Maximum point lights shaded per frame. Extra submissions are dropped,
+/// farthest from the camera first.
+pub const MAX_POINT_LIGHTS: usize = 64;
+
+/// Point light with smooth falloff to zero at `radius`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PointLight {
+    pub position: [f32; 3],
+    pub radius: f32,
+    pub color: [f32; 3],
+    pub _padding: u32,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, color: [f32; 3], radius: f32) -> Self {
+        Self { position: position.to_array(), radius, color, _padding: 0 }
+    }
+}
+
+/// Per-frame culling results (instances, all meshes combined).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CullStats {
+    /// Inside the camera frustum: drawn in the main pass (and the shadow pass if opaque).
+    pub visible: u32,
+    /// Outside the camera but inside the light frustum: shadow pass only.
+    pub shadow_only: u32,
+    /// Outside both frustums: never uploaded.
+    pub culled: u32,
+}
+
+// ============================================================================
+// QUALITY SETTINGS
+// ============================================================================
+
+/// Runtime-adjustable quality options. Apply with `Renderer::set_settings()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderSettings {
+    /// MSAA sample count: 1 (off), 2 or 4. Clamped to what the adapter supports.
+    pub msaa_samples: u32,
+    /// Render the directional shadow map. When off, everything is fully lit.
+    pub shadows: bool,
+    /// Added to the LOD level exponent: +1 halves every switch distance, −1 doubles it.
+    pub lod_bias: f32,
+    /// Add a blurred copy of everything brighter than `bloom_threshold`.
+    pub bloom: bool,
+    /// HDR luminance where bloom starts (with a soft knee below it).
+    pub bloom_threshold: f32,
+    pub bloom_intensity: f32,
+    /// ACES filmic tone map; when off, HDR values simply clip at 1.0.
+    pub tone_mapping: bool,
+    /// Contrast / saturation / tint grade after tone mapping.
+    pub color_grading: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            msaa_samples: 4,
+            shadows: true,
+            lod_bias: 0.0,
+            bloom: true,
+            bloom_threshold: 1.0,
+            bloom_intensity: 0.6,
+            tone_mapping: true,
+            color_grading: true,
+        }
+    }
+}
+
+/// One instanced draw: a run of same-mesh, same-LOD instances in the instance buffer.
+#[derive(Clone)]
+struct Batch {
+    mesh: MeshHandle,
+    lod: usize,
+    instances: std::ops::Range<u32>,
+}
+
+/// Variants of the scene shader's pipeline.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScenePipeline {
+    Opaque,
+    Transparent,
+    Outline,
+}
+
+// ============================================================================
+// FRAME CONTEXT
+// ============================================================================
+
+/// Handed to the overlay callback of `Renderer::render()` after the scene pass,
+/// so UI layers (egui debug overlay, future HUD) can record their own passes
+/// into the same encoder and target.
+pub struct FrameContext<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    pub view: &'a wgpu::TextureView,
+}
+
+// ============================================================================
+// RENDERER
+// ============================================================================
+
+pub struct Renderer {
+    /// Window surface; `None` for headless renderers (see headless.rs).
+    surface: Option<wgpu::Surface<'static>>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    settings: RenderSettings,
+    /// MSAA sample counts usable with both the surface and depth formats.
+    supported_msaa: Vec<u32>,
+    shader: wgpu::ShaderModule,
+    render_pipeline_layout: wgpu::PipelineLayout,
+    render_pipeline: wgpu::RenderPipeline,
+    /// Same shader with alpha blending and no depth writes.
+    transparent_pipeline: wgpu::RenderPipeline,
+    /// Inverted hulls (front faces culled, extruded along normals) in the
+    /// material's outline color.
+    outline_pipeline: wgpu::RenderPipeline,
+    materials: MaterialLibrary,
+    splat: TerrainSplat,
+
+    /// GPU buffers for every procedural mesh, indexed by `MeshHandle`.
+    meshes: MeshRegistry,
+    /// Per-entity instance data for all meshes, grouped into contiguous per-mesh ranges.
+    /// Grows to fit however many instances are submitted.
+    instance_buffer: GrowableBuffer,
+    /// Instances submitted since the last render(), bucketed by mesh handle.
+    submitted: Vec<Vec<InstanceData>>,
+    /// Draw calls issued by the last render() (one per non-empty mesh batch per pass).
+    draw_calls: u32,
+    cull_stats: CullStats,
+    /// Ground decals submitted since the last render().
+    decals: DecalPass,
+    /// Particle billboards submitted since the last render().
+    particles: ParticlePass,
+    /// Particles drawn by the last render() (after culling).
+    particles_drawn: u32,
+    /// Selection rings and health bars submitted since the last render().
+    world_ui: WorldUiPass,
+    /// Entity-ID target and pixel readback for GPU picking.
+    picking: IdBuffer,
+
+    uniforms: Uniforms,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    light: LightUniform,
+    /// Highest ground point; the shadow frustum covers terrain from y = 0 up to it.
+    terrain_height: f32,
+    light_buffer: wgpu::Buffer,
+    /// Fixed-size storage buffer holding up to MAX_POINT_LIGHTS lights.
+    point_light_buffer: wgpu::Buffer,
+    /// Point lights submitted since the last render().
+    point_lights: Vec<PointLight>,
+    /// (shaded, submitted) point lights in the last render().
+    point_light_stats: (u32, u32),
+    light_bind_group: wgpu::BindGroup,
+    shadow_map: ShadowMap,
+    depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+    /// Multisampled color target resolved into the HDR target; `None` when MSAA is off.
+    msaa_view: Option<wgpu::TextureView>,
+    /// HDR scene target, bloom chain and the composite pass onto the surface.
+    post: PostProcess,
+}
+
+impl Renderer {
+    pub async fn new(window: Arc<Window>) -> Self {
+        let size = window.inner_size();
+
+        let instance = Self::create_instance();
+        let surface = instance.create_surface(window).unwrap();
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .unwrap();
+
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            present_mode: surface_caps.present_modes[0],
+            alpha_mode: surface_caps.alpha_modes[0],
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        Self::from_adapter(&adapter, Some(surface), config).await
+    }
+
+    fn create_instance() -> wgpu::Instance {
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        })
+    }
+
+    /// Shared setup for windowed and headless renderers. `config` describes the
+    /// output target; it configures `surface` when there is one.
+    async fn from_adapter(
+        adapter: &wgpu::Adapter,
+        surface: Option<wgpu::Surface<'static>>,
+        config: wgpu::SurfaceConfiguration,
+    ) -> Self {
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::default(),
+                    memory_hints: wgpu::MemoryHints::default(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        if let Some(surface) = &surface {
+            surface.configure(&device, &config);
+        }
+
+        let color_flags = adapter.get_texture_format_features(HDR_FORMAT).flags;
+        let depth_flags = adapter.get_texture_format_features(wgpu::TextureFormat::Depth32Float).flags;
+        let supported_msaa: Vec<u32> = [1, 2, 4].into_iter()
+            .filter(|&n| color_flags.sample_count_supported(n) && depth_flags.sample_count_supported(n))
+            .collect();
+        let mut settings = RenderSettings::default();
+        settings.msaa_samples = Self::clamp_msaa(&supported_msaa, settings.msaa_samples);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_instanced.wgsl").into()),
+        });
+
+        let uniforms = Uniforms::new();
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("uniform_bind_group_layout"),
+            });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("uniform_bind_group"),
+        });
+
+        let light_uniform = LightUniform::new();
+        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Buffer"),
+            contents: bytemuck::cast_slice(&[light_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let point_light_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Point Light Buffer"),
+            size: (MAX_POINT_LIGHTS * std::mem::size_of::<PointLight>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let shadow_map = ShadowMap::new(&device);
+
+        // Light bind group: uniform + shadow map and its comparison sampler for PCF.
+        let light_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Depth,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("light_bind_group_layout"),
+            });
+
+        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &light_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: light_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&shadow_map.depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&shadow_map.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: point_light_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("light_bind_group"),
+        });
+
+        let materials = MaterialLibrary::new(&device);
+        let splat = TerrainSplat::new(&device, &queue);
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[
+                    &uniform_bind_group_layout,
+                    &light_bind_group_layout,
+                    materials.bind_group_layout(),
+                    splat.bind_group_layout(),
+                ],
+                push_constant_ranges: &[],
+            });
+
+        let samples = settings.msaa_samples;
+        let render_pipeline = Self::create_scene_pipeline(&device, &render_pipeline_layout, &shader, samples, ScenePipeline::Opaque);
+        let transparent_pipeline = Self::create_scene_pipeline(&device, &render_pipeline_layout, &shader, samples, ScenePipeline::Transparent);
+        let outline_pipeline = Self::create_scene_pipeline(&device, &render_pipeline_layout, &shader, samples, ScenePipeline::Outline);
+
+        // Instance buffer for per-entity model matrix + color (shared across all meshes).
+        // Initial capacity covers the test scene; grows on demand.
+        let instance_buffer = GrowableBuffer::new(
+            &device,
+            "Instance Buffer",
+            wgpu::BufferUsages::VERTEX,
+            (256 * std::mem::size_of::<InstanceData>()) as u64,
+        );
+
+        let decals = DecalPass::new(&device, &uniform_bind_group_layout, samples);
+        let particles = ParticlePass::new(&device, &uniform_bind_group_layout, samples);
+        let world_ui = WorldUiPass::new(&device, &uniform_bind_group_layout, samples);
+        let picking = IdBuffer::new(&device, &uniform_bind_group_layout, config.width, config.height);
+
+        let (depth_texture, depth_view) = Self::create_depth_texture(&device, &config, samples);
+        let msaa_view = Self::create_msaa_target(&device, &config, samples);
+        let post = PostProcess::new(&device, config.format, config.width, config.height);
+
+        Self {
+            surface,
+            device,
+            queue,
+            config,
+            settings,
+            supported_msaa,
+            shader,
+            render_pipeline_layout,
+            render_pipeline,
+            transparent_pipeline,
+            outline_pipeline,
+            materials,
+            splat,
+            meshes: MeshRegistry::new(),
+            instance_buffer,
+            submitted: Vec::new(),
+            draw_calls: 0,
+            cull_stats: CullStats::default(),
+            decals,
+            particles,
+            particles_drawn: 0,
+            world_ui,
+            picking,
+            uniforms,
+            uniform_buffer,
+            uniform_bind_group,
+            light: light_uniform,
+            terrain_height: 0.0,
+            light_buffer,
+            point_light_buffer,
+            point_lights: Vec::new(),
+            point_light_stats: (0, 0),
+            light_bind_group,
+            shadow_map,
+            depth_texture,
+            depth_view,
+            msaa_view,
+            post,
+        }
+    }
+
+    /// Scene pipeline for opaque (blend replace, depth write), transparent
+    /// (alpha blend, depth test only) or outline (front-face culled hull) geometry.
+    fn create_scene_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        sample_count: u32,
+        kind: ScenePipeline,
+    ) -> wgpu::RenderPipeline {
+        let transparent = kind == ScenePipeline::Transparent;
+        let (label, vs, fs) = match kind {
+            ScenePipeline::Opaque => ("Render Pipeline", "vs_main", "fs_main"),
+            ScenePipeline::Transparent => ("Transparent Pipeline", "vs_main", "fs_main"),
+            ScenePipeline::Outline => ("Outline Pipeline", "vs_outline", "fs_outline"),
+        };
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some(vs),
+                buffers: &[GpuVertex::desc(), InstanceData::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some(fs),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(if transparent { wgpu::BlendState::ALPHA_BLENDING } else { wgpu::BlendState::REPLACE }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(if kind == ScenePipeline::Outline { wgpu::Face::Front } else { wgpu::Face::Back }),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: !transparent,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Largest supported sample count not above `requested` (1 is always supported).
+    fn clamp_msaa(supported: &[u32], requested: u32) -> u32 {
+        supported.iter().copied().filter(|&n| n <= requested).max().unwrap_or(1)
+    }
+
+    fn create_msaa_target(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Option<wgpu::TextureView> {
+        if sample_count <= 1 {
+            return None;
+        }
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("MSAA Color Target"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
+    fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> (wgpu::Texture, wgpu::TextureView) {
+        let size = wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            // Never sampled; GL backends reject sampleable multisampled depth.
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    pub fn device(&self) -> &wgpu::Device { &self.device }
+    pub fn surface_format(&self) -> wgpu::TextureFormat { self.config.format }
+    /// Current target size in physical pixels.
+    pub fn size(&self) -> (u32, u32) { (self.config.width, self.config.height) }
+    pub fn aspect(&self) -> f32 { self.config.width as f32 / self.config.height as f32 }
+    pub fn draw_calls(&self) -> u32 { self.draw_calls }
+    pub fn cull_stats(&self) -> CullStats { self.cull_stats }
+    pub fn particles_drawn(&self) -> u32 { self.particles_drawn }
+    /// Point lights (shaded, submitted) in the last frame; shaded ≤ MAX_POINT_LIGHTS.
+    pub fn point_light_stats(&self) -> (u32, u32) { self.point_light_stats }
+    /// Instance buffer allocation in bytes and number of reallocations so far.
+    pub fn instance_buffer_stats(&self) -> (u64, u32) {
+        (self.instance_buffer.size(), self.instance_buffer.reallocations())
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.config.width = width;
+            self.config.height = height;
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+            self.create_render_targets();
+            self.post.resize(&self.device, width, height);
+            self.picking.resize(&self.device, width, height);
+        }
+    }
+
+    /// (Re)create depth and MSAA targets for the current size and sample count.
+    fn create_render_targets(&mut self) {
+        let samples = self.settings.msaa_samples;
+        let (depth_texture, depth_view) = Self::create_depth_texture(&self.device, &self.config, samples);
+        self.depth_texture = depth_texture;
+        self.depth_view = depth_view;
+        self.msaa_view = Self::create_msaa_target(&self.device, &self.config, samples);
+    }
+
+    pub fn settings(&self) -> RenderSettings { self.settings }
+
+    /// Apply quality settings. Changing the MSAA sample count rebuilds the scene
+    /// pipelines and render targets; the other options take effect next frame.
+    pub fn set_settings(&mut self, settings: RenderSettings) {
+        let samples = Self::clamp_msaa(&self.supported_msaa, settings.msaa_samples);
+        let msaa_changed = samples != self.settings.msaa_samples;
+        self.settings = RenderSettings { msaa_samples: samples, ..settings };
+        if msaa_changed {
+            self.render_pipeline = Self::create_scene_pipeline(&self.device, &self.render_pipeline_layout, &self.shader, samples, ScenePipeline::Opaque);
+            self.transparent_pipeline = Self::create_scene_pipeline(&self.device, &self.render_pipeline_layout, &self.shader, samples, ScenePipeline::Transparent);
+            self.outline_pipeline = Self::create_scene_pipeline(&self.device, &self.render_pipeline_layout, &self.shader, samples, ScenePipeline::Outline);
+            self.decals.set_sample_count(&self.device, samples);
+            self.particles.set_sample_count(&self.device, samples);
+            self.world_ui.set_sample_count(&self.device, samples);
+            self.create_render_targets();
+        }
+    }
+
+    /// Upload a mesh and return the handle to attach to entities.
+    pub fn register_mesh(&mut self, label: &str, mesh: &RenderMesh) -> MeshHandle {
+        self.register_mesh_lods(label, std::slice::from_ref(mesh), f32::INFINITY)
+    }
+
+    /// Upload LOD levels (finest first). Level 1 takes over at `lod_distance`
+    /// from the camera, each further level at double the previous distance.
+    pub fn register_mesh_lods(&mut self, label: &str, lods: &[RenderMesh], lod_distance: f32) -> MeshHandle {
+        let handle = self.meshes.register(&self.device, label, lods, lod_distance);
+        self.submitted.resize_with(self.meshes.mesh_count(), Vec::new);
+        handle
+    }
+
+    /// Add a material and return the handle to attach to entities.
+    pub fn register_material(&mut self, material: Material) -> MaterialHandle {
+        self.materials.register(&self.device, &self.queue, material)
+    }
+
+    /// Terrain layers blended over materials with `Material::splat` set.
+    pub fn set_splat_map(&mut self, map: &SplatMap) {
+        self.splat.set(&self.device, &self.queue, map);
+    }
+
+    /// Highest terrain point (ground spans y = 0 up to it), so the shadow
+    /// frustum still covers raised ground and the units standing on it.
+    pub fn set_terrain_height(&mut self, max_height: f32) {
+        self.terrain_height = max_height;
+    }
+
+    /// Camera view-projection and eye position for this frame.
+    pub fn set_camera(&mut self, view_proj: Mat4, camera_pos: Vec3) {
+        self.uniforms.view_proj = view_proj.to_cols_array_2d();
+        self.uniforms.camera_pos = camera_pos.to_array();
+    }
+
+    /// Directional light for subsequent frames. `view_proj` is ignored; the
+    /// renderer refits the shadow frustum to the camera each frame.
+    pub fn set_light(&mut self, light: LightUniform) {
+        self.light = light;
+    }
+
+    /// Queue one instance of `mesh` for the next render().
+    pub fn submit(&mut self, mesh: MeshHandle, material: MaterialHandle, model: Mat4, color: [f32; 4], emissive: f32) {
+        self.submitted[mesh.0 as usize].push(InstanceData::new(model, material, color, emissive));
+    }
+
+    /// Queue a point light for the next render().
+    pub fn submit_point_light(&mut self, light: PointLight) {
+        self.point_lights.push(light);
+    }
+
+    /// Like `submit`, but the instance also writes `pick_id` (non-zero) into
+    /// the pick buffer so `request_pick` can find it.
+    pub fn submit_pickable(&mut self, mesh: MeshHandle, material: MaterialHandle, model: Mat4, color: [f32; 4], emissive: f32, pick_id: u64) {
+        debug_assert_ne!(pick_id, 0, "pick ID 0 means not pickable");
+        let mut instance = InstanceData::new(model, material, color, emissive);
+        instance.pick_id = [pick_id as u32, (pick_id >> 32) as u32];
+        self.submitted[mesh.0 as usize].push(instance);
+    }
+
+    /// Read the pick ID under window pixel `pixel` (origin top-left) on the
+    /// next frame. The result arrives asynchronously in `last_pick`.
+    pub fn request_pick(&mut self, pixel: (u32, u32)) {
+        self.picking.request(pixel);
+    }
+
+    /// Newest completed pick; lags its request by a frame or two.
+    pub fn last_pick(&self) -> Option<PickResult> { self.picking.last() }
+
+    /// Queue a ground decal for the next render(), as a triangle list of
+    /// (world position, offset from the decal center) vertices already laid
+    /// on the ground. `half_size` is the decal's extent along its local X/Z.
+    pub fn submit_decal(&mut self, shape: DecalShape, half_size: Vec2, color: [f32; 4], triangles: &[(Vec3, Vec2)]) {
+        self.decals.submit(shape, half_size, color, triangles);
+    }
+
+    /// Queue a particle billboard for the next render().
+    pub fn submit_particle(&mut self, blend: ParticleBlend, particle: ParticleInstance) {
+        self.particles.submit(blend, particle);
+    }
+
+    /// Queue a flat ring of outer `radius` at `center`. Lift `center` slightly
+    /// above the ground so the depth test does not z-fight.
+    pub fn submit_selection_ring(&mut self, center: Vec3, radius: f32, color: [f32; 4]) {
+        self.world_ui.submit_ring(center, radius, color);
+    }
+
+    /// Queue a camera-facing bar of `width` centered at `center`. `health` and
+    /// `shield` are fill fractions; `None` hides the shield row.
+    pub fn submit_status_bar(&mut self, center: Vec3, width: f32, health: f32, shield: Option<f32>, color: [f32; 4]) {
+        self.world_ui.submit_bar(center, width, health, shield, color);
+    }
+
+    /// Draw all submitted instances to the surface, run `overlay` for UI passes, and present.
+    /// Clears the submission list for the next frame.
+    pub fn render(&mut self, overlay: impl FnOnce(FrameContext)) -> Result<(), wgpu::SurfaceError> {
+        let surface = self.surface.as_ref().expect("render() needs a window surface; use render_to_image() when headless");
+        let output = surface.get_current_texture()?;
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render Encoder"),
+        });
+
+        self.encode_scene(&mut encoder);
+        self.post.encode(&self.queue, &mut encoder, &view, &self.settings);
+
+        overlay(FrameContext {
+            device: &self.device,
+            queue: &self.queue,
+            encoder: &mut encoder,
+            view: &view,
+        });
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.picking.after_submit();
+        output.present();
+        Ok(())
+    }
+
+    /// Keep the MAX_POINT_LIGHTS lights nearest the camera and upload them.
+    fn upload_point_lights(&mut self) {
+        let submitted = self.point_lights.len();
+        if submitted > MAX_POINT_LIGHTS {
+            let eye = Vec3::from(self.uniforms.camera_pos);
+            let dist_sq = |l: &PointLight| Vec3::from(l.position).distance_squared(eye);
+            self.point_lights.select_nth_unstable_by(MAX_POINT_LIGHTS - 1, |a, b| {
+                dist_sq(a).total_cmp(&dist_sq(b))
+            });
+            self.point_lights.truncate(MAX_POINT_LIGHTS);
+        }
+        if !self.point_lights.is_empty() {
+            self.queue.write_buffer(&self.point_light_buffer, 0, bytemuck::cast_slice(&self.point_lights));
+        }
+        self.uniforms.point_light_count = self.point_lights.len() as u32;
+        self.point_light_stats = (self.point_lights.len() as u32, submitted as u32);
+        self.point_lights.clear();
+    }
+
+    /// Upload uniforms + instances and record the shadow and main scene passes
+    /// into the HDR target.
+    fn encode_scene(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.picking.receive(&self.device);
+        self.upload_point_lights();
+        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniforms]));
+
+        let light_view_proj = shadow::fit_light_view_proj(
+            Mat4::from_cols_array_2d(&self.uniforms.view_proj),
+            Vec3::from(self.light.direction),
+            self.terrain_height,
+        );
+        self.light.view_proj = light_view_proj.to_cols_array_2d();
+        self.queue.write_buffer(&self.light_buffer, 0, bytemuck::cast_slice(&[self.light]));
+        self.shadow_map.update(&self.queue, light_view_proj);
+
+        // Cull and flatten buckets so each mesh occupies one contiguous range of the
+        // instance buffer: camera-visible opaque instances first (front to back, for
+        // early depth rejection, which also keeps LOD levels contiguous), then
+        // shadow-only casters at the coarsest level. Transparent instances go after
+        // all opaque ranges, sorted back to front across meshes.
+        let camera_frustum = Frustum::from_view_projection(Mat4::from_cols_array_2d(&self.uniforms.view_proj));
+        let light_frustum = Frustum::from_view_projection(light_view_proj);
+        let eye = Vec3::from(self.uniforms.camera_pos);
+        let shadows = self.settings.shadows;
+        let lod_bias = self.settings.lod_bias;
+        let mut stats = CullStats::default();
+        let mut instance_data: Vec<InstanceData> = Vec::new();
+        let mut visible: Vec<(f32, InstanceData)> = Vec::new();
+        let mut shadow_only: Vec<InstanceData> = Vec::new();
+        let mut transparent: Vec<(f32, MeshHandle, InstanceData)> = Vec::new();
+        let mut opaque_batches: Vec<Batch> = Vec::new();
+        let mut shadow_batches: Vec<Batch> = Vec::new();
+        for (i, bucket) in self.submitted.iter_mut().enumerate() {
+            let handle = MeshHandle(i as u32);
+            let mesh = self.meshes.get(handle);
+            for instance in bucket.drain(..) {
+                let model = Mat4::from_cols_array_2d(&instance.model);
+                let center = model.transform_point3(mesh.bounds_center);
+                let max_scale = model.x_axis.truncate().length()
+                    .max(model.y_axis.truncate().length())
+                    .max(model.z_axis.truncate().length());
+                let radius = mesh.bounds_radius * max_scale;
+                let is_transparent = self.materials.get(MaterialHandle(instance.material)).transparent;
+                if camera_frustum.intersects_sphere(center, radius) {
+                    let dist = center.distance(eye);
+                    if is_transparent {
+                        transparent.push((dist, handle, instance));
+                    } else {
+                        visible.push((dist, instance));
+                    }
+                } else if shadows && !is_transparent && light_frustum.intersects_sphere(center, radius) {
+                    shadow_only.push(instance);
+                } else {
+                    stats.culled += 1;
+                }
+            }
+
+            visible.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+            stats.visible += visible.len() as u32;
+            for (dist, instance) in visible.drain(..) {
+                push_batched(&mut opaque_batches, &mut instance_data, handle, mesh.select_lod(dist, lod_bias), instance);
+            }
+
+            if !shadow_only.is_empty() {
+                stats.shadow_only += shadow_only.len() as u32;
+                let start = instance_data.len() as u32;
+                instance_data.append(&mut shadow_only);
+                shadow_batches.push(Batch {
+                    mesh: handle,
+                    lod: mesh.levels.len() - 1,
+                    instances: start..instance_data.len() as u32,
+                });
+            }
+        }
+
+        // Outlines redraw every opaque batch holding an outlined instance; the
+        // outline shader collapses the batch's other instances.
+        let outline_batches: Vec<Batch> = opaque_batches.iter()
+            .filter(|batch| {
+                instance_data[batch.instances.start as usize..batch.instances.end as usize].iter()
+                    .any(|instance| self.materials.get(MaterialHandle(instance.material)).outline_width > 0.0)
+            })
+            .cloned()
+            .collect();
+
+        // Transparent: back to front; consecutive instances of one mesh + LOD share a draw.
+        transparent.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+        stats.visible += transparent.len() as u32;
+        let mut transparent_batches: Vec<Batch> = Vec::new();
+        for (dist, handle, instance) in transparent {
+            let lod = self.meshes.get(handle).select_lod(dist, lod_bias);
+            push_batched(&mut transparent_batches, &mut instance_data, handle, lod, instance);
+        }
+        self.cull_stats = stats;
+
+        self.instance_buffer.write(&self.device, &self.queue, bytemuck::cast_slice(&instance_data));
+        self.decals.upload(&self.device, &self.queue);
+        self.particles_drawn = self.particles.upload(&self.device, &self.queue, &camera_frustum, eye);
+        self.world_ui.upload(&self.device, &self.queue, &camera_frustum);
+
+        // Shadow pass: opaque batches (visible + shadow-only) cast into the light's
+        // depth map. With shadows off the pass only clears it, leaving everything lit.
+        let mut draw_calls = 0;
+        {
+            let mut shadow_pass = self.shadow_map.begin_pass(encoder);
+            if shadows {
+                shadow_pass.set_vertex_buffer(1, self.instance_buffer.buffer().slice(..));
+                draw_calls += draw_batches(&mut shadow_pass, &self.meshes, &opaque_batches);
+                draw_calls += draw_batches(&mut shadow_pass, &self.meshes, &shadow_batches);
+            }
+        }
+
+        // With MSAA, render into the multisampled target and resolve into the HDR target.
+        let view = self.post.hdr_view();
+        let (target, resolve_target) = match &self.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(view)),
+            None => (view, None),
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.05, g: 0.05, b: 0.1, a: 1.0 }),
+                    // The multisampled target is only needed until it is resolved.
+                    store: if resolve_target.is_some() { wgpu::StoreOp::Discard } else { wgpu::StoreOp::Store },
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.light_bind_group, &[]);
+        render_pass.set_bind_group(2, self.materials.bind_group(), &[]);
+        render_pass.set_bind_group(3, self.splat.bind_group(), &[]);
+        render_pass.set_vertex_buffer(1, self.instance_buffer.buffer().slice(..));
+
+        // Opaque: one instanced draw per mesh + LOD in view
+        draw_calls += draw_batches(&mut render_pass, &self.meshes, &opaque_batches);
+
+        // Outlines: inverted hulls behind their opaque meshes
+        if !outline_batches.is_empty() {
+            render_pass.set_pipeline(&self.outline_pipeline);
+            draw_calls += draw_batches(&mut render_pass, &self.meshes, &outline_batches);
+        }
+
+        // Decals: blended onto the ground, hidden by anything standing in front
+        draw_calls += self.decals.draw(&mut render_pass);
+
+        // Transparent: blended over the opaque scene in sorted order
+        render_pass.set_pipeline(&self.transparent_pipeline);
+        draw_calls += draw_batches(&mut render_pass, &self.meshes, &transparent_batches);
+
+        // Particles last, in the same pass so the MSAA target never needs storing
+        draw_calls += self.particles.draw(&mut render_pass);
+
+        // World-space UI: rings, then bars on top of everything
+        draw_calls += self.world_ui.draw(&mut render_pass);
+        drop(render_pass);
+
+        // Pick IDs: the visible opaque batches again, only when a pick is pending
+        if self.picking.wants_pass() {
+            {
+                let mut pick_pass = self.picking.begin_pass(encoder);
+                pick_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                pick_pass.set_vertex_buffer(1, self.instance_buffer.buffer().slice(..));
+                draw_calls += draw_batches(&mut pick_pass, &self.meshes, &opaque_batches);
+            }
+            self.picking.copy_request(encoder);
+        }
+
+        self.draw_calls = draw_calls;
+    }
+}
+
+/// Append `instance` to `instance_data`, extending the last batch if it is the
+/// same mesh + LOD, otherwise starting a new one.
+fn push_batched(batches: &mut Vec<Batch>, instance_data: &mut Vec<InstanceData>, mesh: MeshHandle, lod: usize, instance: InstanceData) {
+    let index = instance_data.len() as u32;
+    instance_data.push(instance);
+    match batches.last_mut() {
+        Some(last) if last.mesh == mesh && last.lod == lod && last.instances.end == index => {
+            last.instances.end = index + 1;
+        }
+        _ => batches.push(Batch { mesh, lod, instances: index..index + 1 }),
+    }
+}
+
+/// One instanced draw per batch. The instance buffer must already be bound to slot 1.
+/// Returns the number of draw calls issued.
+fn draw_batches(pass: &mut wgpu::RenderPass, meshes: &MeshRegistry, batches: &[Batch]) -> u32 {
+    for batch in batches {
+        let mesh = &meshes.get(batch.mesh).levels[batch.lod];
+        pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..mesh.num_indices, 0, batch.instances.clone());
+    }
+    batches.len() as u32
+}
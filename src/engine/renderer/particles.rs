@@ -0,0 +1,217 @@
+// Particle billboards: camera-facing quads expanded in the vertex shader.
+// See docs/research/rendering-architecture.md §"Particles".
+//
+// Particles are simulated on the CPU (engine/particles.rs) and submitted each
+// frame as points with a size and color. Alpha-blended particles are sorted
+// back to front; additive ones are order-independent. Both draw after the
+// transparent meshes, depth-tested against the scene without writing depth.
+
+use glam::Vec3;
+use super::super::frustum::Frustum;
+use super::growable_buffer::GrowableBuffer;
+use super::post::HDR_FORMAT;
+
+/// How a particle combines with what is behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleBlend {
+    /// Standard over-blending, e.g. smoke and dust. Sorted back to front.
+    Alpha,
+    /// Adds light, e.g. sparks, muzzle flashes and fire. Unsorted.
+    Additive,
+}
+
+/// GPU layout of one billboard (32 bytes).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ParticleInstance {
+    pub position: [f32; 3],
+    /// Edge length of the quad in world units.
+    pub size: f32,
+    /// Linear HDR color; alpha scales coverage (and brightness when additive).
+    pub color: [f32; 4],
+}
+
+impl ParticleInstance {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ParticleInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Pipelines and instance buffer for both particle blend modes.
+pub struct ParticlePass {
+    shader: wgpu::ShaderModule,
+    layout: wgpu::PipelineLayout,
+    alpha_pipeline: wgpu::RenderPipeline,
+    additive_pipeline: wgpu::RenderPipeline,
+    buffer: GrowableBuffer,
+    /// Particles submitted since the last upload, per blend mode.
+    alpha: Vec<ParticleInstance>,
+    additive: Vec<ParticleInstance>,
+    /// Instance ranges of the last upload: alpha first, then additive.
+    alpha_count: u32,
+    additive_count: u32,
+}
+
+impl ParticlePass {
+    /// `camera_layout` is the scene's group-0 layout (view-projection uniforms).
+    pub fn new(device: &wgpu::Device, camera_layout: &wgpu::BindGroupLayout, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_particles.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Pipeline Layout"),
+            bind_group_layouts: &[camera_layout],
+            push_constant_ranges: &[],
+        });
+        let alpha_pipeline = create_pipeline(device, &layout, &shader, sample_count, ParticleBlend::Alpha);
+        let additive_pipeline = create_pipeline(device, &layout, &shader, sample_count, ParticleBlend::Additive);
+        let buffer = GrowableBuffer::new(
+            device,
+            "Particle Buffer",
+            wgpu::BufferUsages::VERTEX,
+            (1024 * std::mem::size_of::<ParticleInstance>()) as u64,
+        );
+        Self {
+            shader,
+            layout,
+            alpha_pipeline,
+            additive_pipeline,
+            buffer,
+            alpha: Vec::new(),
+            additive: Vec::new(),
+            alpha_count: 0,
+            additive_count: 0,
+        }
+    }
+
+    /// Rebuild the pipelines for a new MSAA sample count.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.alpha_pipeline = create_pipeline(device, &self.layout, &self.shader, sample_count, ParticleBlend::Alpha);
+        self.additive_pipeline = create_pipeline(device, &self.layout, &self.shader, sample_count, ParticleBlend::Additive);
+    }
+
+    pub fn submit(&mut self, blend: ParticleBlend, particle: ParticleInstance) {
+        match blend {
+            ParticleBlend::Alpha => self.alpha.push(particle),
+            ParticleBlend::Additive => self.additive.push(particle),
+        }
+    }
+
+    /// Cull against the camera, sort alpha particles back to front and upload.
+    /// Returns the number of particles that will be drawn.
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, frustum: &Frustum, eye: Vec3) -> u32 {
+        // A quad of edge `size` fits in a sphere of radius size·√2/2 whatever its orientation.
+        let visible = |p: &ParticleInstance| {
+            frustum.intersects_sphere(Vec3::from(p.position), p.size * std::f32::consts::FRAC_1_SQRT_2)
+        };
+        self.alpha.retain(visible);
+        self.additive.retain(visible);
+
+        let dist_sq = |p: &ParticleInstance| Vec3::from(p.position).distance_squared(eye);
+        self.alpha.sort_unstable_by(|a, b| dist_sq(b).total_cmp(&dist_sq(a)));
+
+        self.alpha_count = self.alpha.len() as u32;
+        self.additive_count = self.additive.len() as u32;
+        self.alpha.append(&mut self.additive);
+        self.buffer.write(device, queue, bytemuck::cast_slice(&self.alpha));
+        self.alpha.clear();
+        self.alpha_count + self.additive_count
+    }
+
+    /// Draw the last upload. Group 0 (camera uniforms) must already be bound.
+    /// Returns the number of draw calls issued.
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) -> u32 {
+        let mut draw_calls = 0;
+        pass.set_vertex_buffer(0, self.buffer.buffer().slice(..));
+        if self.alpha_count > 0 {
+            pass.set_pipeline(&self.alpha_pipeline);
+            pass.draw(0..6, 0..self.alpha_count);
+            draw_calls += 1;
+        }
+        if self.additive_count > 0 {
+            pass.set_pipeline(&self.additive_pipeline);
+            pass.draw(0..6, self.alpha_count..self.alpha_count + self.additive_count);
+            draw_calls += 1;
+        }
+        draw_calls
+    }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    sample_count: u32,
+    blend: ParticleBlend,
+) -> wgpu::RenderPipeline {
+    let (label, blend) = match blend {
+        ParticleBlend::Alpha => ("Particle Alpha Pipeline", wgpu::BlendState::ALPHA_BLENDING),
+        ParticleBlend::Additive => ("Particle Additive Pipeline", wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::Zero,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+        }),
+    };
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_particle"),
+            buffers: &[ParticleInstance::desc()],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs_particle"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: HDR_FORMAT,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
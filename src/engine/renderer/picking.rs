@@ -0,0 +1,242 @@
+// GPU picking: an entity-ID buffer read back one pixel at a time.
+// See docs/research/rendering-architecture.md §"Picking".
+//
+// When a pick is requested, the frame draws the visible opaque batches a
+// second time into an Rg32Uint target (64-bit instance pick IDs as low/high
+// words, 0 = nothing) with
+// its own single-sample depth buffer, then copies the texel under the cursor
+// into a small readback buffer. The buffer is mapped after submission and
+// collected on a later frame, so the CPU never waits on the GPU: results lag
+// the request by a frame or two. While a readback is in flight new requests
+// wait, and no ID pass is drawn.
+
+use std::sync::{Arc, OnceLock};
+use super::InstanceData;
+use super::super::mesh::GpuVertex;
+
+const ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg32Uint;
+/// One texel, padded to the row alignment texture-to-buffer copies need.
+const READBACK_SIZE: u64 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64;
+
+/// A completed pick: the requested pixel and the ID drawn there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickResult {
+    pub pixel: (u32, u32),
+    /// `None` over background or instances submitted without a pick ID.
+    pub id: Option<u64>,
+}
+
+enum Readback {
+    Idle,
+    /// Copy of `pixel` recorded into the frame; map once it is submitted.
+    Copied((u32, u32)),
+    /// Map requested; the callback stores whether it succeeded.
+    Mapping((u32, u32), Arc<OnceLock<bool>>),
+}
+
+/// ID target, pipeline and readback state.
+pub struct IdBuffer {
+    pipeline: wgpu::RenderPipeline,
+    id_texture: wgpu::Texture,
+    id_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    readback: wgpu::Buffer,
+    state: Readback,
+    /// Pixel to pick on the next frame with a free readback buffer.
+    request: Option<(u32, u32)>,
+    last: Option<PickResult>,
+}
+
+impl IdBuffer {
+    /// `camera_layout` is the scene's group-0 layout (view-projection uniforms).
+    pub fn new(device: &wgpu::Device, camera_layout: &wgpu::BindGroupLayout, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Pick Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_pick.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Pick Pipeline Layout"),
+            bind_group_layouts: &[camera_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Pick Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_pick"),
+                buffers: &[GpuVertex::desc(), InstanceData::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_pick"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ID_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pick Readback Buffer"),
+            size: READBACK_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let (id_texture, id_view, depth_view) = create_targets(device, width, height);
+        Self {
+            pipeline,
+            id_texture,
+            id_view,
+            depth_view,
+            readback,
+            state: Readback::Idle,
+            request: None,
+            last: None,
+        }
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        (self.id_texture, self.id_view, self.depth_view) = create_targets(device, width, height);
+    }
+
+    /// Ask for the ID under `pixel`; replaces any request not yet drawn.
+    pub fn request(&mut self, pixel: (u32, u32)) {
+        self.request = Some(pixel);
+    }
+
+    /// Newest completed pick, if any has finished yet.
+    pub fn last(&self) -> Option<PickResult> { self.last }
+
+    /// Collect a finished readback. Call once per frame before encoding.
+    pub fn receive(&mut self, device: &wgpu::Device) {
+        let Readback::Mapping(pixel, done) = &self.state else { return };
+        device.poll(wgpu::Maintain::Poll);
+        let Some(&mapped) = done.get() else { return };
+        if mapped {
+            let id = {
+                let bytes = self.readback.slice(..8).get_mapped_range();
+                u64::from_le_bytes(bytes[..8].try_into().expect("8-byte texel"))
+            };
+            self.readback.unmap();
+            self.last = Some(PickResult { pixel: *pixel, id: (id != 0).then_some(id) });
+        }
+        self.state = Readback::Idle;
+    }
+
+    /// Whether this frame should draw the ID pass: a pick is pending and the
+    /// readback buffer is free.
+    pub fn wants_pass(&self) -> bool {
+        self.request.is_some() && matches!(self.state, Readback::Idle)
+    }
+
+    /// Begin the ID pass (cleared to 0) with the pick pipeline bound. The
+    /// caller binds group 0 and the instance buffer and draws opaque batches.
+    pub fn begin_pass<'e>(&self, encoder: &'e mut wgpu::CommandEncoder) -> wgpu::RenderPass<'e> {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Pick Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.id_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass
+    }
+
+    /// Record the copy of the requested texel. Call after the ID pass ends.
+    pub fn copy_request(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let Some(pixel) = self.request.take() else { return };
+        let size = self.id_texture.size();
+        let (x, y) = (pixel.0.min(size.width - 1), pixel.1.min(size.height - 1));
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(READBACK_SIZE as u32),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+        );
+        self.state = Readback::Copied(pixel);
+    }
+
+    /// Start mapping the readback buffer. Call after the frame that recorded
+    /// the copy has been submitted.
+    pub fn after_submit(&mut self) {
+        let Readback::Copied(pixel) = self.state else { return };
+        let done = Arc::new(OnceLock::new());
+        let callback_done = Arc::clone(&done);
+        self.readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let _ = callback_done.set(result.is_ok());
+        });
+        self.state = Readback::Mapping(pixel, done);
+    }
+}
+
+fn create_targets(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView, wgpu::TextureView) {
+    let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+    let id_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Pick ID Target"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: ID_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Pick Depth"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Depth32Float,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let id_view = id_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (id_texture, id_view, depth_view)
+}
@@ -0,0 +1,362 @@
+// Post-processing: HDR scene target → bloom → tone map / colour grade → surface.
+// See docs/research/rendering-architecture.md §"HDR and Post-Processing".
+//
+// The scene pass renders linear colour into an Rgba16Float target so emissive
+// surfaces and point-lit highlights can exceed 1.0. Bloom thresholds that
+// target into a half-resolution chain of BLOOM_LEVELS textures (each half the
+// previous), then walks back up with a tent filter, adding each level into the
+// next larger one. The composite pass adds bloom, applies exposure + ACES and a
+// fixed colour grade, and writes to the (sRGB) surface.
+
+use super::RenderSettings;
+
+/// Format of the offscreen scene target.
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+/// Bloom chain length (level 0 is half resolution).
+const BLOOM_LEVELS: usize = 5;
+/// Width of the soft knee below the bloom threshold.
+const BLOOM_KNEE: f32 = 0.5;
+
+// Colour grade: slight contrast and saturation lift with a warm tint.
+const EXPOSURE: f32 = 1.0;
+const GRADE_CONTRAST: f32 = 1.05;
+const GRADE_SATURATION: f32 = 1.1;
+const GRADE_TINT: [f32; 3] = [1.02, 1.0, 0.97];
+
+const FLAG_BLOOM: u32 = 1;
+const FLAG_TONE_MAP: u32 = 2;
+const FLAG_COLOR_GRADE: u32 = 4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct BloomParams {
+    source_texel: [f32; 2],
+    threshold: f32,
+    knee: f32,
+    prefilter: u32,
+    _padding: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct CompositeParams {
+    bloom_intensity: f32,
+    exposure: f32,
+    contrast: f32,
+    saturation: f32,
+    tint: [f32; 3],
+    flags: u32,
+}
+
+/// Size-dependent textures and the bind groups that read them.
+struct PostTargets {
+    hdr_size: (u32, u32),
+    hdr_view: wgpu::TextureView,
+    bloom_views: Vec<wgpu::TextureView>,
+    bloom_sizes: Vec<(u32, u32)>,
+    /// Downsample pass i reads the HDR target (i = 0) or bloom level i − 1.
+    downsample_groups: Vec<wgpu::BindGroup>,
+    /// Upsample pass i reads bloom level i + 1 and adds into level i.
+    upsample_groups: Vec<wgpu::BindGroup>,
+    composite_group: wgpu::BindGroup,
+}
+
+/// Size-independent resources the target bind groups are built from.
+struct PostResources {
+    sampler: wgpu::Sampler,
+    bloom_layout: wgpu::BindGroupLayout,
+    composite_layout: wgpu::BindGroupLayout,
+    downsample_params: Vec<wgpu::Buffer>,
+    upsample_params: Vec<wgpu::Buffer>,
+    composite_params: wgpu::Buffer,
+}
+
+pub struct PostProcess {
+    resources: PostResources,
+    downsample_pipeline: wgpu::RenderPipeline,
+    upsample_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    targets: PostTargets,
+}
+
+impl PostProcess {
+    /// `output_format` is the format the composite pass writes (the surface).
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let sampler_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        };
+        let uniform_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let bloom_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[texture_entry(0), sampler_entry(1), uniform_entry(2)],
+            label: Some("bloom_bind_group_layout"),
+        });
+        let composite_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[texture_entry(0), texture_entry(1), sampler_entry(2), uniform_entry(3)],
+            label: Some("composite_bind_group_layout"),
+        });
+
+        let bloom_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bloom Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_bloom.wgsl").into()),
+        });
+        let composite_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Composite Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_composite.wgsl").into()),
+        });
+
+        let additive = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent::REPLACE,
+        };
+        let downsample_pipeline = fullscreen_pipeline(device, "Bloom Downsample", &bloom_layout, &bloom_shader, "fs_downsample", HDR_FORMAT, None);
+        let upsample_pipeline = fullscreen_pipeline(device, "Bloom Upsample", &bloom_layout, &bloom_shader, "fs_upsample", HDR_FORMAT, Some(additive));
+        let composite_pipeline = fullscreen_pipeline(device, "Composite", &composite_layout, &composite_shader, "fs_composite", output_format, None);
+
+        let params_buffer = |label| device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: std::mem::size_of::<BloomParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let downsample_params: Vec<wgpu::Buffer> = (0..BLOOM_LEVELS).map(|_| params_buffer("Bloom Downsample Params")).collect();
+        let upsample_params: Vec<wgpu::Buffer> = (0..BLOOM_LEVELS - 1).map(|_| params_buffer("Bloom Upsample Params")).collect();
+        let composite_params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Composite Params"),
+            size: std::mem::size_of::<CompositeParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let resources = PostResources {
+            sampler,
+            bloom_layout,
+            composite_layout,
+            downsample_params,
+            upsample_params,
+            composite_params,
+        };
+        let targets = Self::create_targets(device, &resources, width, height);
+
+        Self {
+            resources,
+            downsample_pipeline,
+            upsample_pipeline,
+            composite_pipeline,
+            targets,
+        }
+    }
+
+    fn create_targets(device: &wgpu::Device, resources: &PostResources, width: u32, height: u32) -> PostTargets {
+        let PostResources { sampler, bloom_layout, composite_layout, downsample_params, upsample_params, composite_params } = resources;
+        let create_view = |label, width, height| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: HDR_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            }).create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let hdr_view = create_view("HDR Scene Target", width, height);
+        let bloom_sizes: Vec<(u32, u32)> = (0..BLOOM_LEVELS)
+            .map(|i| ((width >> (i + 1)).max(1), (height >> (i + 1)).max(1)))
+            .collect();
+        let bloom_views: Vec<wgpu::TextureView> = bloom_sizes.iter()
+            .map(|&(w, h)| create_view("Bloom Level", w, h))
+            .collect();
+
+        let bloom_group = |source: &wgpu::TextureView, params: &wgpu::Buffer| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: bloom_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(sampler) },
+                    wgpu::BindGroupEntry { binding: 2, resource: params.as_entire_binding() },
+                ],
+                label: Some("bloom_bind_group"),
+            })
+        };
+        let downsample_groups = (0..BLOOM_LEVELS)
+            .map(|i| bloom_group(if i == 0 { &hdr_view } else { &bloom_views[i - 1] }, &downsample_params[i]))
+            .collect();
+        let upsample_groups = (0..BLOOM_LEVELS - 1)
+            .map(|i| bloom_group(&bloom_views[i + 1], &upsample_params[i]))
+            .collect();
+
+        let composite_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: composite_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&hdr_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&bloom_views[0]) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(sampler) },
+                wgpu::BindGroupEntry { binding: 3, resource: composite_params.as_entire_binding() },
+            ],
+            label: Some("composite_bind_group"),
+        });
+
+        PostTargets { hdr_size: (width, height), hdr_view, bloom_views, bloom_sizes, downsample_groups, upsample_groups, composite_group }
+    }
+
+    /// Recreate the HDR and bloom targets for a new surface size.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.targets = Self::create_targets(device, &self.resources, width, height);
+    }
+
+    /// Single-sample HDR target the scene pass renders (or resolves) into.
+    pub fn hdr_view(&self) -> &wgpu::TextureView { &self.targets.hdr_view }
+
+    /// Record bloom (if enabled) and the composite pass into `output`.
+    pub fn encode(&self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, output: &wgpu::TextureView, settings: &RenderSettings) {
+        let targets = &self.targets;
+
+        if settings.bloom {
+            let texel = |(w, h): (u32, u32)| [1.0 / w as f32, 1.0 / h as f32];
+            let params = |source_size, prefilter| BloomParams {
+                source_texel: texel(source_size),
+                threshold: settings.bloom_threshold,
+                knee: BLOOM_KNEE,
+                prefilter: prefilter as u32,
+                _padding: [0; 3],
+            };
+
+            // Down: HDR → level 0 (thresholded) → level 1 → ...
+            let mut source_size = targets.hdr_size;
+            for i in 0..BLOOM_LEVELS {
+                queue.write_buffer(&self.resources.downsample_params[i], 0, bytemuck::cast_slice(&[params(source_size, i == 0)]));
+                fullscreen_pass(encoder, "Bloom Downsample Pass", &targets.bloom_views[i], wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    &self.downsample_pipeline, &targets.downsample_groups[i]);
+                source_size = targets.bloom_sizes[i];
+            }
+
+            // Up: smallest level added into each larger one, ending at level 0.
+            for i in (0..BLOOM_LEVELS - 1).rev() {
+                queue.write_buffer(&self.resources.upsample_params[i], 0, bytemuck::cast_slice(&[params(targets.bloom_sizes[i + 1], false)]));
+                fullscreen_pass(encoder, "Bloom Upsample Pass", &targets.bloom_views[i], wgpu::LoadOp::Load,
+                    &self.upsample_pipeline, &targets.upsample_groups[i]);
+            }
+        }
+
+        let flags = if settings.bloom { FLAG_BLOOM } else { 0 }
+            | if settings.tone_mapping { FLAG_TONE_MAP } else { 0 }
+            | if settings.color_grading { FLAG_COLOR_GRADE } else { 0 };
+        let composite = CompositeParams {
+            bloom_intensity: settings.bloom_intensity,
+            exposure: EXPOSURE,
+            contrast: GRADE_CONTRAST,
+            saturation: GRADE_SATURATION,
+            tint: GRADE_TINT,
+            flags,
+        };
+        queue.write_buffer(&self.resources.composite_params, 0, bytemuck::cast_slice(&[composite]));
+        fullscreen_pass(encoder, "Composite Pass", output, wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            &self.composite_pipeline, &targets.composite_group);
+    }
+}
+
+fn fullscreen_pipeline(
+    device: &wgpu::Device,
+    label: &str,
+    layout: &wgpu::BindGroupLayout,
+    shader: &wgpu::ShaderModule,
+    fragment_entry: &str,
+    format: wgpu::TextureFormat,
+    blend: Option<wgpu::BlendState>,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(label),
+        bind_group_layouts: &[layout],
+        push_constant_ranges: &[],
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_fullscreen"),
+            buffers: &[],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(fragment_entry),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// One fullscreen-triangle pass into `target`.
+fn fullscreen_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    label: &str,
+    target: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+) {
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations { load, store: wgpu::StoreOp::Store },
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+    pass.set_pipeline(pipeline);
+    pass.set_bind_group(0, bind_group, &[]);
+    pass.draw(0..3, 0..1);
+}
@@ -0,0 +1,285 @@
+// Directional shadow map: depth-only pass from the sun, sampled with PCF.
+// See docs/research/lighting-implementation.md §"Shadows".
+//
+// Each frame the light's orthographic frustum is fitted to the patch of ground
+// the camera can see (the four frustum-corner rays intersected with y = 0 and
+// with the top of the terrain), extended upward to cover units standing on the
+// highest ground and back toward the light so off-screen casters still throw
+// shadows into view. The fitted square's side is rounded up
+// to a multiple of EXTENT_QUANTUM, so the texel size only changes in steps when
+// zooming, and its corner is snapped to whole texels in a light space anchored
+// at the world origin, so the map doesn't shimmer as the camera pans.
+
+use glam::{Mat4, Vec3, Vec4};
+use super::InstanceData;
+use super::super::mesh::GpuVertex;
+
+/// Shadow map resolution (square).
+pub const SHADOW_MAP_SIZE: u32 = 2048;
+const SHADOW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+/// Tallest shadow caster above the ground it stands on (world units).
+const CASTER_HEIGHT: f32 = 3.0;
+/// How far behind the visible area (toward the light) casters are still captured.
+const CASTER_REACH: f32 = 40.0;
+/// The light frustum's side length is rounded up to a multiple of this (world units).
+const EXTENT_QUANTUM: f32 = 8.0;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadowUniforms {
+    light_view_proj: [[f32; 4]; 4],
+}
+
+pub struct ShadowMap {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// Depth view rendered into by the shadow pass.
+    pub depth_view: wgpu::TextureView,
+    /// Comparison sampler for PCF lookups in the main pass.
+    pub sampler: wgpu::Sampler,
+}
+
+impl ShadowMap {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d {
+                width: SHADOW_MAP_SIZE,
+                height: SHADOW_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SHADOW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let depth_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow Uniform Buffer"),
+            size: std::mem::size_of::<ShadowUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("shadow_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("shadow_bind_group"),
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shadow Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_shadow.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_shadow"),
+                buffers: &[GpuVertex::desc(), InstanceData::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: None,
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: SHADOW_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                // Slope-scaled bias keeps curved unit surfaces free of acne.
+                bias: wgpu::DepthBiasState {
+                    constant: 2,
+                    slope_scale: 2.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self { pipeline, uniform_buffer, bind_group, depth_view, sampler }
+    }
+
+    /// Upload the light's view-projection for this frame's shadow pass.
+    pub fn update(&self, queue: &wgpu::Queue, light_view_proj: Mat4) {
+        let uniforms = ShadowUniforms { light_view_proj: light_view_proj.to_cols_array_2d() };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    /// Begin the depth-only shadow pass with the shadow pipeline and bind group set.
+    /// The caller binds vertex/instance buffers and issues the draws.
+    pub fn begin_pass<'e>(&'e self, encoder: &'e mut wgpu::CommandEncoder) -> wgpu::RenderPass<'e> {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shadow Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass
+    }
+}
+
+/// Orthographic light view-projection covering the ground the camera can see.
+///
+/// `camera_view_proj` is the camera's combined matrix; `light_dir` points from
+/// the light toward the scene; the ground spans y = 0 up to `terrain_height`.
+/// Frustum-corner rays that never reach a height (aimed above the horizon) are
+/// clamped to the far plane.
+pub fn fit_light_view_proj(camera_view_proj: Mat4, light_dir: Vec3, terrain_height: f32) -> Mat4 {
+    let inv = camera_view_proj.inverse();
+    let unproject = |x: f32, y: f32, z: f32| {
+        let p = inv * Vec4::new(x, y, z, 1.0);
+        p.truncate() / p.w
+    };
+
+    // Visible ground lies between where each corner ray crosses the top of the
+    // terrain and where it reaches y = 0. Both crossings go in at ground level
+    // and lifted to caster height above the highest ground.
+    let top = terrain_height.max(0.0);
+    let mut points: Vec<Vec3> = Vec::with_capacity(16);
+    for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+        let near = unproject(x, y, 0.0);
+        let far = unproject(x, y, 1.0);
+        for level in [0.0, top] {
+            let hit = if near.y <= level {
+                near
+            } else if far.y < level {
+                near + (far - near) * ((near.y - level) / (near.y - far.y))
+            } else {
+                far
+            };
+            points.push(Vec3::new(hit.x, 0.0, hit.z));
+            points.push(Vec3::new(hit.x, top + CASTER_HEIGHT, hit.z));
+        }
+    }
+
+    // Rotation-only light view: a world point keeps its light-space XY however
+    // the camera moves, so texel snapping below is stable.
+    let dir = light_dir.normalize_or_zero();
+    let up = if dir.cross(Vec3::Y).length_squared() < 1e-4 { Vec3::Z } else { Vec3::Y };
+    let view = Mat4::look_at_rh(-dir, Vec3::ZERO, up);
+
+    let mut min = Vec3::splat(f32::INFINITY);
+    let mut max = Vec3::splat(f32::NEG_INFINITY);
+    for p in &points {
+        let l = view.transform_point3(*p);
+        min = min.min(l);
+        max = max.max(l);
+    }
+
+    // Square XY extent of quantized size whose min corner sits on a whole
+    // texel, so panning moves the map in texel steps and the texel size holds
+    // still. Flooring the corner shifts the square by up to one texel, so the
+    // extent keeps a texel of slack: extent ≥ span + extent / SHADOW_MAP_SIZE.
+    let size = SHADOW_MAP_SIZE as f32;
+    let span = (max.x - min.x).max(max.y - min.y) * size / (size - 1.0);
+    let extent = (span / EXTENT_QUANTUM).ceil().max(1.0) * EXTENT_QUANTUM;
+    let texel = extent / size;
+    let (min_x, min_y) = ((min.x / texel).floor() * texel, (min.y / texel).floor() * texel);
+    let (max_x, max_y) = (min_x + extent, min_y + extent);
+
+    // Light-space view looks down -Z: nearest point has the largest z.
+    let near = -max.z - CASTER_REACH;
+    let far = -min.z + 1.0;
+    Mat4::orthographic_rh(min_x, max_x, min_y, max_y, near, far) * view
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Vec3, Vec4Swizzles};
+    use super::*;
+
+    /// Light-space NDC of `p` under `light_view_proj`.
+    fn ndc(light_view_proj: Mat4, p: Vec3) -> Vec3 {
+        let clip = light_view_proj * p.extend(1.0);
+        clip.xyz() / clip.w
+    }
+
+    #[test]
+    fn fitted_bounds_cover_the_footprint_at_every_offset() {
+        // Pan in sub-texel steps so the min corner snap lands at every phase.
+        let light_dir = Vec3::new(-0.4, -1.0, -0.3);
+        for terrain_height in [0.0, 3.0] {
+            for step in 0..64 {
+                let target = Vec3::new(step as f32 * 0.013, 0.0, step as f32 * 0.007);
+                let eye = target + Vec3::new(0.0, 24.0, 17.0);
+                let camera = Mat4::perspective_rh(20f32.to_radians(), 16.0 / 9.0, 0.1, 200.0)
+                    * Mat4::look_at_rh(eye, target, Vec3::Y);
+                let light = fit_light_view_proj(camera, light_dir, terrain_height);
+                let inv = camera.inverse();
+                for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                    let near = inv.project_point3(Vec3::new(x, y, 0.0));
+                    let far = inv.project_point3(Vec3::new(x, y, 1.0));
+                    for level in [0.0, terrain_height] {
+                        let ground = near + (far - near) * ((near.y - level) / (near.y - far.y));
+                        let caster = ground + Vec3::Y * CASTER_HEIGHT;
+                        for p in [ground, caster] {
+                            let q = ndc(light, p);
+                            assert!(
+                                q.x.abs() <= 1.0 && q.y.abs() <= 1.0 && (0.0..=1.0).contains(&q.z),
+                                "height {terrain_height}, step {step}: {p} at {q}",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// Terrain splat map: per-tile layer weights blended over the ground by the
+// scene shader. See docs/research/rendering-architecture.md §"Terrain".
+//
+// One RGBA8 texel per tile holds the coverage of three layers painted over
+// the material's own color (R, G, B; A unused). Linear filtering blends
+// neighbouring tiles and the shader roughens the blend with world-space noise,
+// so tile borders read as ragged edges rather than a grid. Only materials with
+// `Material::splat` set sample it; until a map is set it is a single empty
+// texel.
+
+use glam::Vec2;
+
+/// Layer coverage per tile plus the layer colors, as handed to the renderer.
+#[derive(Debug, Clone)]
+pub struct SplatMap {
+    pub width: u32,
+    pub height: u32,
+    /// World X/Z of texel (0, 0)'s min corner.
+    pub origin: Vec2,
+    /// World size of one texel (tile).
+    pub tile_size: f32,
+    /// Row-major by Z, `width * height` texels: coverage of layers 0–2 in RGB.
+    pub weights: Vec<[u8; 4]>,
+    /// Linear RGB of layers 0–2.
+    pub layer_colors: [[f32; 3]; 3],
+}
+
+/// GPU layout of the splat uniforms (std140: 64 bytes).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SplatUniforms {
+    /// xy = world origin, zw = 1 / world extent, mapping world X/Z to UV.
+    rect: [f32; 4],
+    layer_colors: [[f32; 4]; 3],
+}
+
+/// Splat texture, sampler and uniforms bound as the scene shader's group 3.
+pub struct TerrainSplat {
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+}
+
+impl TerrainSplat {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("splat_bind_group_layout"),
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Splat Uniform Buffer"),
+            size: std::mem::size_of::<SplatUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Splat Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let empty = SplatMap {
+            width: 1,
+            height: 1,
+            origin: Vec2::ZERO,
+            tile_size: 1.0,
+            weights: vec![[0; 4]],
+            layer_colors: [[0.0; 3]; 3],
+        };
+        let bind_group = Self::upload(device, queue, &bind_group_layout, &uniform_buffer, &sampler, &empty);
+        Self { bind_group_layout, bind_group, uniform_buffer, sampler }
+    }
+
+    /// Replace the splat map (any size).
+    pub fn set(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, map: &SplatMap) {
+        self.bind_group = Self::upload(device, queue, &self.bind_group_layout, &self.uniform_buffer, &self.sampler, map);
+    }
+
+    fn upload(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        sampler: &wgpu::Sampler,
+        map: &SplatMap,
+    ) -> wgpu::BindGroup {
+        assert_eq!(map.weights.len(), (map.width * map.height) as usize, "splat map size mismatch");
+        let size = wgpu::Extent3d { width: map.width, height: map.height, depth_or_array_layers: 1 };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Splat Map"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            bytemuck::cast_slice(&map.weights),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * map.width),
+                rows_per_image: Some(map.height),
+            },
+            size,
+        );
+
+        let extent = Vec2::new(map.width as f32, map.height as f32) * map.tile_size;
+        let uniforms = SplatUniforms {
+            rect: [map.origin.x, map.origin.y, 1.0 / extent.x, 1.0 / extent.y],
+            layer_colors: map.layer_colors.map(|[r, g, b]| [r, g, b, 1.0]),
+        };
+        queue.write_buffer(uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&view) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(sampler) },
+            ],
+            label: Some("splat_bind_group"),
+        })
+    }
+
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout { &self.bind_group_layout }
+    pub fn bind_group(&self) -> &wgpu::BindGroup { &self.bind_group }
+}
@@ -0,0 +1,214 @@
+// World-space UI: selection rings and health/shield bars.
+// See docs/research/rendering-architecture.md §"World-Space UI".
+//
+// Rings lie flat just above the ground and are depth-tested, so units stand
+// in them. Bars face the camera and ignore depth so terrain and neighbours
+// never hide them. Each kind is one instanced draw.
+
+use glam::Vec3;
+use super::super::frustum::Frustum;
+use super::growable_buffer::GrowableBuffer;
+use super::post::HDR_FORMAT;
+
+/// GPU layout of one ring or bar (48 bytes).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct UiInstance {
+    /// Ring: ground center. Bar: bar center.
+    position: [f32; 3],
+    /// Ring: outer radius. Bar: width (height is a fixed fraction of it).
+    size: f32,
+    color: [f32; 4],
+    /// Bar only: health and shield fill; shield < 0 hides the shield row.
+    fill: [f32; 2],
+    _padding: [f32; 2],
+}
+
+impl UiInstance {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const COLUMN: wgpu::BufferAddress = std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress;
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<UiInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: COLUMN * 2,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+}
+
+/// Pipelines and instance buffer for rings and bars.
+pub struct WorldUiPass {
+    shader: wgpu::ShaderModule,
+    layout: wgpu::PipelineLayout,
+    ring_pipeline: wgpu::RenderPipeline,
+    bar_pipeline: wgpu::RenderPipeline,
+    buffer: GrowableBuffer,
+    /// Submitted since the last upload.
+    rings: Vec<UiInstance>,
+    bars: Vec<UiInstance>,
+    /// Instance ranges of the last upload: rings first, then bars.
+    ring_count: u32,
+    bar_count: u32,
+}
+
+impl WorldUiPass {
+    /// `camera_layout` is the scene's group-0 layout (view-projection uniforms).
+    pub fn new(device: &wgpu::Device, camera_layout: &wgpu::BindGroupLayout, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("World UI Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shader_world_ui.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("World UI Pipeline Layout"),
+            bind_group_layouts: &[camera_layout],
+            push_constant_ranges: &[],
+        });
+        let ring_pipeline = create_pipeline(device, &layout, &shader, sample_count, false);
+        let bar_pipeline = create_pipeline(device, &layout, &shader, sample_count, true);
+        let buffer = GrowableBuffer::new(
+            device,
+            "World UI Buffer",
+            wgpu::BufferUsages::VERTEX,
+            (256 * std::mem::size_of::<UiInstance>()) as u64,
+        );
+        Self {
+            shader,
+            layout,
+            ring_pipeline,
+            bar_pipeline,
+            buffer,
+            rings: Vec::new(),
+            bars: Vec::new(),
+            ring_count: 0,
+            bar_count: 0,
+        }
+    }
+
+    /// Rebuild the pipelines for a new MSAA sample count.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.ring_pipeline = create_pipeline(device, &self.layout, &self.shader, sample_count, false);
+        self.bar_pipeline = create_pipeline(device, &self.layout, &self.shader, sample_count, true);
+    }
+
+    pub fn submit_ring(&mut self, center: Vec3, radius: f32, color: [f32; 4]) {
+        self.rings.push(UiInstance {
+            position: center.to_array(),
+            size: radius,
+            color,
+            fill: [0.0; 2],
+            _padding: [0.0; 2],
+        });
+    }
+
+    pub fn submit_bar(&mut self, center: Vec3, width: f32, health: f32, shield: Option<f32>, color: [f32; 4]) {
+        self.bars.push(UiInstance {
+            position: center.to_array(),
+            size: width,
+            color,
+            fill: [health.clamp(0.0, 1.0), shield.map_or(-1.0, |s| s.clamp(0.0, 1.0))],
+            _padding: [0.0; 2],
+        });
+    }
+
+    /// Cull against the camera and upload.
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, frustum: &Frustum) {
+        // Both quads fit in a sphere of their half-extent·√2; size is the
+        // ring radius (half-extent) or bar width (twice the half-extent).
+        self.rings.retain(|r| frustum.intersects_sphere(Vec3::from(r.position), r.size * std::f32::consts::SQRT_2));
+        self.bars.retain(|b| frustum.intersects_sphere(Vec3::from(b.position), b.size * std::f32::consts::FRAC_1_SQRT_2));
+
+        self.ring_count = self.rings.len() as u32;
+        self.bar_count = self.bars.len() as u32;
+        self.rings.append(&mut self.bars);
+        self.buffer.write(device, queue, bytemuck::cast_slice(&self.rings));
+        self.rings.clear();
+    }
+
+    /// Draw the last upload: rings, then bars over everything. Group 0 (camera
+    /// uniforms) must already be bound. Returns the number of draw calls issued.
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) -> u32 {
+        let mut draw_calls = 0;
+        pass.set_vertex_buffer(0, self.buffer.buffer().slice(..));
+        if self.ring_count > 0 {
+            pass.set_pipeline(&self.ring_pipeline);
+            pass.draw(0..6, 0..self.ring_count);
+            draw_calls += 1;
+        }
+        if self.bar_count > 0 {
+            pass.set_pipeline(&self.bar_pipeline);
+            pass.draw(0..6, self.ring_count..self.ring_count + self.bar_count);
+            draw_calls += 1;
+        }
+        draw_calls
+    }
+}
+
+/// Ring (depth-tested) or bar (always on top) pipeline; both alpha-blended
+/// without depth writes.
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    sample_count: u32,
+    bar: bool,
+) -> wgpu::RenderPipeline {
+    let (label, vs, fs) = if bar {
+        ("World UI Bar Pipeline", "vs_bar", "fs_bar")
+    } else {
+        ("World UI Ring Pipeline", "vs_ring", "fs_ring")
+    };
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some(vs),
+            buffers: &[UiInstance::desc()],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(fs),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: HDR_FORMAT,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: if bar { wgpu::CompareFunction::Always } else { wgpu::CompareFunction::Less },
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
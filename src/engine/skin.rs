@@ -10,7 +10,7 @@
 //   degree 2 (chain through)   → smooth tube pass-through
 //   degree 3+ (junction)       → cross-section merge (Gram-Schmidt frame + stitching)
 
-use glam::Vec3;
+use glam::{Quat, Vec3};
 use super::mesh::PolyMesh;
 
 // ============================================================================
@@ -30,16 +30,50 @@ pub struct SkinEdge {
     pub b: usize,
 }
 
+/// A named attachment point (weapon muzzle, hardpoint, carried resource) bound to a node.
+///
+/// Sockets reference their node by index rather than copying its position, so
+/// moving the node (posing/animation) carries the socket along with it.
+#[derive(Debug, Clone)]
+pub struct SkinSocket {
+    pub name: String,
+    pub node: usize,  // index into SkinGraph::nodes
+    /// Model-space displacement from the node centre (e.g. out to the skin surface).
+    pub offset: Vec3,
+    /// Model-space orientation. The socket's forward axis is -Z after rotation.
+    pub orientation: Quat,
+}
+
+impl SkinSocket {
+    /// Resolve against `node_positions` (one per graph node, rest or posed).
+    pub fn resolve(&self, node_positions: &[Vec3]) -> SocketPose {
+        SocketPose {
+            name:     self.name.clone(),
+            position: node_positions[self.node] + self.offset,
+            rotation: self.orientation,
+        }
+    }
+}
+
+/// Model-space transform of a socket, resolved against the current node positions.
+#[derive(Debug, Clone)]
+pub struct SocketPose {
+    pub name: String,
+    pub position: Vec3,
+    pub rotation: Quat,
+}
+
 /// Input graph for the Skin Modifier.
 /// Nodes are joints, edges are bones. Typically 10–50 nodes per unit.
 pub struct SkinGraph {
     pub nodes: Vec<SkinNode>,
     pub edges: Vec<SkinEdge>,
+    pub sockets: Vec<SkinSocket>,
 }
 
 impl SkinGraph {
     pub fn new() -> Self {
-        Self { nodes: Vec::new(), edges: Vec::new() }
+        Self { nodes: Vec::new(), edges: Vec::new(), sockets: Vec::new() }
     }
 
     /// Add a node at the given position with the given radius. Returns its index.
@@ -49,6 +83,19 @@ impl SkinGraph {
         idx
     }
 
+    /// Tag `node` as a named socket. Returns the socket's index.
+    pub fn add_socket(&mut self, node: usize, name: &str, offset: Vec3, orientation: Quat) -> usize {
+        debug_assert!(node < self.nodes.len(), "Socket must reference an existing node");
+        let idx = self.sockets.len();
+        self.sockets.push(SkinSocket { name: name.to_string(), node, offset, orientation });
+        idx
+    }
+
+    /// Current node positions, in node order: the pose sockets resolve against.
+    pub fn node_positions(&self) -> Vec<Vec3> {
+        self.nodes.iter().map(|n| n.position).collect()
+    }
+
     /// Number of edges incident to the given node.
     fn degree(&self, node_idx: usize) -> usize {
         self.edges.iter()
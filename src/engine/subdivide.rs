@@ -185,3 +185,192 @@ pub fn subdivide(mesh: &PolyMesh, levels: u32) -> PolyMesh {
     }
     current
 }
+
+// ============================================================================
+// LIMIT SURFACE
+// ============================================================================
+
+/// A vertex projected onto the Catmull-Clark limit surface.
+#[derive(Debug, Clone, Copy)]
+pub struct LimitVertex {
+    pub position: Vec3,
+    /// Unit-length surface normal at `position`.
+    pub normal:   Vec3,
+}
+
+/// Evaluate the limit position and limit normal of every vertex of a mesh.
+///
+/// Intended for the output of `catmull_clark()` / `subdivide()` (levels ≥ 1),
+/// which is all-quad. Gives the exact point the vertex would converge to after
+/// infinitely many subdivision levels — used for picking, attachment points and
+/// tight bounding volumes without subdividing further.
+///
+/// Interior vertex of valence n with ordered one-ring (edge neighbours e_i,
+/// face-diagonal neighbours f_i between e_i and e_{i+1}):
+///   limit = (n²·V + 4·Σe_i + Σf_i) / (n·(n+5))
+///   t1    = Σ A·cos(2πi/n)·e_i + (cos(2πi/n) + cos(2π(i+1)/n))·f_i
+///   t2    = same with sin
+///   normal = t1 × t2, oriented to agree with the area-weighted face normal
+/// where A = 1 + cos(2π/n) + cos(π/n)·√(2·(9 + cos(2π/n))).
+///
+/// Vertices that are not surrounded by a closed fan of quads (boundary vertices,
+/// vertices touching n-gons) keep their position and use the area-weighted normal.
+pub fn limit_vertices(mesh: &PolyMesh) -> Vec<LimitVertex> {
+    let n_verts = mesh.vertex_count();
+
+    // vertex_faces[v] = (face index, position of v within that face)
+    let mut vertex_faces: Vec<Vec<(usize, usize)>> = vec![vec![]; n_verts];
+    let mut area_normals: Vec<Vec3> = vec![Vec3::ZERO; n_verts];
+    for (fi, face) in mesh.faces.iter().enumerate() {
+        let n = face.len();
+        for (k, &v) in face.iter().enumerate() {
+            vertex_faces[v].push((fi, k));
+        }
+        for i in 1..(n - 1) {
+            let a = mesh.positions[face[0]];
+            let b = mesh.positions[face[i]];
+            let c = mesh.positions[face[i + 1]];
+            let weighted_normal = (b - a).cross(c - a);
+            area_normals[face[0]]     += weighted_normal;
+            area_normals[face[i]]     += weighted_normal;
+            area_normals[face[i + 1]] += weighted_normal;
+        }
+    }
+
+    (0..n_verts).map(|v| {
+        let fallback_normal = area_normals[v].normalize_or_zero();
+        let Some((edge_ring, diag_ring)) = one_ring(mesh, &vertex_faces[v], v) else {
+            return LimitVertex { position: mesh.positions[v], normal: fallback_normal };
+        };
+
+        let n  = edge_ring.len();
+        let nf = n as f32;
+        let pv = mesh.positions[v];
+        let sum_e: Vec3 = edge_ring.iter().map(|&e| mesh.positions[e]).sum();
+        let sum_f: Vec3 = diag_ring.iter().map(|&f| mesh.positions[f]).sum();
+        let position = (nf * nf * pv + 4.0 * sum_e + sum_f) / (nf * (nf + 5.0));
+
+        let step = std::f32::consts::TAU / nf;
+        let a = 1.0 + step.cos() + (step * 0.5).cos() * (2.0 * (9.0 + step.cos())).sqrt();
+        let mut t1 = Vec3::ZERO;
+        let mut t2 = Vec3::ZERO;
+        for i in 0..n {
+            let (s0, c0) = (step * i as f32).sin_cos();
+            let (s1, c1) = (step * (i + 1) as f32).sin_cos();
+            let e = mesh.positions[edge_ring[i]];
+            let f = mesh.positions[diag_ring[i]];
+            t1 += a * c0 * e + (c0 + c1) * f;
+            t2 += a * s0 * e + (s0 + s1) * f;
+        }
+
+        let mut normal = t1.cross(t2).normalize_or_zero();
+        if normal == Vec3::ZERO {
+            normal = fallback_normal;
+        } else if normal.dot(fallback_normal) < 0.0 {
+            normal = -normal;
+        }
+        LimitVertex { position, normal }
+    }).collect()
+}
+
+/// Walk the quads around interior vertex `v` in rotational order.
+///
+/// Returns `(edge_ring, diag_ring)` where `diag_ring[i]` is the vertex opposite
+/// `v` in the quad spanned by `edge_ring[i]` and `edge_ring[i + 1]`.
+/// Returns `None` if any adjacent face is not a quad or the fan is not closed.
+fn one_ring(
+    mesh: &PolyMesh,
+    faces_of_v: &[(usize, usize)],
+    v: usize,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    if faces_of_v.len() < 3 { return None; }
+    if faces_of_v.iter().any(|&(fi, _)| mesh.faces[fi].len() != 4) { return None; }
+
+    let mut edge_ring = Vec::with_capacity(faces_of_v.len());
+    let mut diag_ring = Vec::with_capacity(faces_of_v.len());
+    let (mut fi, mut k) = faces_of_v[0];
+    for _ in 0..faces_of_v.len() {
+        let face = &mesh.faces[fi];
+        edge_ring.push(face[(k + 1) % 4]);
+        diag_ring.push(face[(k + 2) % 4]);
+        // The next quad shares edge (v, prev) and traverses it as v → prev.
+        let prev = face[(k + 3) % 4];
+        let &(next_fi, next_k) = faces_of_v.iter()
+            .find(|&&(g, gk)| mesh.faces[g][(gk + 1) % 4] == prev && g != fi)?;
+        fi = next_fi;
+        k = next_k;
+    }
+    // A closed fan returns to the starting face after exactly `valence` steps.
+    if fi != faces_of_v[0].0 { return None; }
+    debug_assert_eq!(mesh.faces[fi][k], v);
+    Some((edge_ring, diag_ring))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cube with corners at ±1, quads wound CCW seen from outside.
+    fn cube() -> PolyMesh {
+        let mut mesh = PolyMesh::new();
+        for i in 0..8 {
+            let bit = |b: usize| if i & b != 0 { 1.0 } else { -1.0 };
+            mesh.add_vertex(Vec3::new(bit(1), bit(2), bit(4)));
+        }
+        for face in [[0, 4, 6, 2], [1, 3, 7, 5], [0, 1, 5, 4], [2, 6, 7, 3], [0, 2, 3, 1], [4, 5, 7, 6]] {
+            mesh.add_face(face.to_vec());
+        }
+        mesh
+    }
+
+    fn edge_count(mesh: &PolyMesh) -> usize {
+        mesh.faces.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    #[test]
+    fn cube_limit_matches_closed_form() {
+        // The corners' limit points, from the level-0 valence-3 mask
+        // (9·V + 4·Σe + Σf) / 24, sit at ±1/2; the face centres', from the
+        // regular mask on level 1, at ±68/81 along their axis.
+        let level1 = subdivide(&cube(), 1);
+        let limit = limit_vertices(&level1);
+        let corners = edge_count(&cube());
+        for (v, corner) in cube().positions.iter().enumerate() {
+            let p = limit[corners + v].position;
+            assert!((p - *corner * 0.5).length() < 1e-5, "corner {v}: {p}");
+        }
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z, Vec3::NEG_X, Vec3::NEG_Y, Vec3::NEG_Z] {
+            let i = level1.positions.iter().position(|&p| p == axis).expect("face point");
+            let p = limit[i].position;
+            assert!((p - axis * (68.0 / 81.0)).length() < 1e-5, "face centre {axis}: {p}");
+        }
+    }
+
+    #[test]
+    fn limit_positions_are_invariant_under_subdivision() {
+        // Vertex points follow the edge points in catmull_clark() output, so
+        // vertex v of one level is vertex edges + v of the next. Both must
+        // converge to the same point on the limit surface.
+        for level in 1..=2 {
+            let mesh = subdivide(&cube(), level);
+            let finer = catmull_clark(&mesh);
+            let (coarse_limit, fine_limit) = (limit_vertices(&mesh), limit_vertices(&finer));
+            let offset = edge_count(&mesh);
+            for (v, lv) in coarse_limit.iter().enumerate() {
+                let fine = fine_limit[offset + v];
+                assert!((lv.position - fine.position).length() < 1e-5, "L{level} vertex {v}");
+                assert!(lv.normal.dot(fine.normal) > 1.0 - 1e-5, "L{level} vertex {v} normal");
+            }
+        }
+    }
+
+    #[test]
+    fn limit_normals_are_unit_and_outward() {
+        let mesh = subdivide(&cube(), 2);
+        for (v, lv) in limit_vertices(&mesh).iter().enumerate() {
+            assert!((lv.normal.length() - 1.0).abs() < 1e-5, "vertex {v}: |n| = {}", lv.normal.length());
+            // The limit surface is convex and centred on the origin.
+            assert!(lv.normal.dot(lv.position) > 0.0, "vertex {v} normal points inward");
+        }
+    }
+}
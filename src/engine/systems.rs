@@ -1,5 +1,29 @@
 // ECS systems for updating game state
 // Systems operate on entities with specific component combinations
 //
-// Currently empty - systems are implemented inline in main.rs
-// This file is kept for future system implementations
+// Most systems are still implemented inline in main.rs; queries that other
+// engine modules need (sockets, ...) live here.
+
+use bevy_ecs::prelude::*;
+use glam::Mat4;
+use super::components::{AttachmentSockets, SkinPose, Transform, socket_world_matrix};
+
+/// World transforms of every attachment socket on `entity`, in declaration order.
+/// Sockets are resolved against the entity's current `SkinPose`, so they follow
+/// the posed model. Returns an empty list if the entity has no `Transform`,
+/// `AttachmentSockets` or `SkinPose`.
+pub fn socket_world_transforms(world: &World, entity: Entity) -> Vec<(String, Mat4)> {
+    let (Some(transform), Some(sockets), Some(pose)) = (
+        world.get::<Transform>(entity),
+        world.get::<AttachmentSockets>(entity),
+        world.get::<SkinPose>(entity),
+    ) else {
+        return Vec::new();
+    };
+    sockets.resolve(pose)
+        .map(|s| {
+            let matrix = socket_world_matrix(transform, &s);
+            (s.name, matrix)
+        })
+        .collect()
+}
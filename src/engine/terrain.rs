@@ -0,0 +1,427 @@
+// Heightmap terrain: per-tile heights with ramps and cliffs.
+// See docs/research/rendering-architecture.md §"Terrain".
+//
+// Each tile stores the heights of its four corners, so neighbouring tiles may
+// disagree along their shared edge. Flat tiles have four equal corners, ramp
+// tiles slope linearly between two levels, and a height step between tiles is
+// a cliff: the mesh closes it with a vertical wall and navigation treats the
+// high side as unwalkable.
+//
+// Tiles also carry a ground type (grass, dirt, rock) and an optional creep
+// overlay. The same data paints the ground through the renderer's splat map
+// and sets the navigation cost of each cell, so what looks rough is slow.
+//
+// The tile grid lines up with the navigation grid (one tile per nav cell), and
+// the mesh is split into CHUNK_TILES × CHUNK_TILES chunks so the renderer can
+// frustum-cull them independently.
+
+use std::ops::Range;
+use glam::{Vec2, Vec3};
+use super::mesh::{GpuVertex, RenderMesh};
+use super::navigation::NavigationGrid;
+use super::picking::Ray;
+use super::renderer::SplatMap;
+
+/// Tiles along each side of one terrain mesh chunk.
+pub const CHUNK_TILES: u32 = 16;
+
+/// Edge height differences at or below this are continuous ground, not a cliff.
+const CLIFF_EPSILON: f32 = 0.01;
+
+/// Navigation cost multiplier on tiles covered by creep.
+const CREEP_COST: f32 = 2.0;
+
+/// Ground type of a tile. Grass is the ground material's own color; the
+/// others are splat layers painted over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TerrainType {
+    #[default]
+    Grass,
+    Dirt,
+    Rock,
+}
+
+impl TerrainType {
+    /// Navigation cost of crossing one tile (1.0 = open ground; never below
+    /// 1.0 so A*'s distance heuristic stays admissible).
+    pub fn nav_cost(self) -> f32 {
+        match self {
+            Self::Grass | Self::Dirt => 1.0,
+            Self::Rock => 1.5,
+        }
+    }
+}
+
+/// Direction in which a ramp climbs from its low to its high level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RampDirection {
+    PosX,
+    NegX,
+    PosZ,
+    NegZ,
+}
+
+/// Tile heightmap. Tile (x, z) covers `origin + [x, x+1) × [z, z+1)` tile sizes.
+pub struct Terrain {
+    width: u32,
+    height: u32,
+    origin: Vec2,
+    tile_size: f32,
+    /// Corner heights per tile, ordered [(x0,z0), (x1,z0), (x0,z1), (x1,z1)].
+    corners: Vec<[f32; 4]>,
+    /// Highest corner anywhere; kept current by `set_level` and `ramp`.
+    max_height: f32,
+    types: Vec<TerrainType>,
+    creep: Vec<bool>,
+}
+
+impl Terrain {
+    /// Flat grass at height 0. `origin` is the world X/Z of tile (0, 0)'s min corner.
+    pub fn new(width: u32, height: u32, origin: Vec2, tile_size: f32) -> Self {
+        Self {
+            width,
+            height,
+            origin,
+            tile_size,
+            corners: vec![[0.0; 4]; (width * height) as usize],
+            max_height: 0.0,
+            types: vec![TerrainType::Grass; (width * height) as usize],
+            creep: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// Set the ground type of every tile in the rectangle.
+    pub fn paint(&mut self, xs: Range<u32>, zs: Range<u32>, terrain_type: TerrainType) {
+        self.paint_where(terrain_type, |x, z| xs.contains(&x) && zs.contains(&z));
+    }
+
+    /// Set the ground type of every tile for which `inside(x, z)` holds.
+    pub fn paint_where(&mut self, terrain_type: TerrainType, inside: impl Fn(u32, u32) -> bool) {
+        for z in 0..self.height {
+            for x in 0..self.width {
+                if inside(x, z) {
+                    let i = self.idx(x, z);
+                    self.types[i] = terrain_type;
+                }
+            }
+        }
+    }
+
+    /// Cover every tile whose center lies within `radius` tiles of tile
+    /// (`cx`, `cz`)'s center with creep.
+    pub fn spread_creep(&mut self, cx: u32, cz: u32, radius: f32) {
+        for z in 0..self.height {
+            for x in 0..self.width {
+                let d = Vec2::new(x as f32 - cx as f32, z as f32 - cz as f32);
+                if d.length() <= radius {
+                    let i = self.idx(x, z);
+                    self.creep[i] = true;
+                }
+            }
+        }
+    }
+
+    /// Make every tile in the rectangle flat at `level`.
+    pub fn set_level(&mut self, xs: Range<u32>, zs: Range<u32>, level: f32) {
+        for z in zs.start..zs.end.min(self.height) {
+            for x in xs.start..xs.end.min(self.width) {
+                let i = self.idx(x, z);
+                self.corners[i] = [level; 4];
+            }
+        }
+        self.update_max_height();
+    }
+
+    /// Slope the rectangle from `low` to `high`, climbing in `direction` across
+    /// its full length. Edges that meet ground at the same levels join it
+    /// seamlessly; the ramp's sides become cliffs wherever they stand proud.
+    pub fn ramp(&mut self, xs: Range<u32>, zs: Range<u32>, direction: RampDirection, low: f32, high: f32) {
+        let length = match direction {
+            RampDirection::PosX | RampDirection::NegX => xs.len(),
+            RampDirection::PosZ | RampDirection::NegZ => zs.len(),
+        } as f32;
+        for z in zs.start..zs.end.min(self.height) {
+            for x in xs.start..xs.end.min(self.width) {
+                let corner = |dx: u32, dz: u32| {
+                    let t = match direction {
+                        RampDirection::PosX => (x + dx - xs.start) as f32,
+                        RampDirection::NegX => (xs.end - x - dx) as f32,
+                        RampDirection::PosZ => (z + dz - zs.start) as f32,
+                        RampDirection::NegZ => (zs.end - z - dz) as f32,
+                    } / length;
+                    low + (high - low) * t
+                };
+                let i = self.idx(x, z);
+                self.corners[i] = [corner(0, 0), corner(1, 0), corner(0, 1), corner(1, 1)];
+            }
+        }
+        self.update_max_height();
+    }
+
+    /// Highest surface point on the map.
+    pub fn max_height(&self) -> f32 {
+        self.max_height
+    }
+
+    fn update_max_height(&mut self) {
+        self.max_height = self.corners.iter().flatten().copied().fold(f32::NEG_INFINITY, f32::max);
+    }
+
+    /// Surface height at world (x, z). Positions off the map take the height
+    /// of the nearest edge tile.
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        let fx = (x - self.origin.x) / self.tile_size;
+        let fz = (z - self.origin.y) / self.tile_size;
+        let tx = (fx.floor().max(0.0) as u32).min(self.width - 1);
+        let tz = (fz.floor().max(0.0) as u32).min(self.height - 1);
+        let u = (fx - tx as f32).clamp(0.0, 1.0);
+        let v = (fz - tz as f32).clamp(0.0, 1.0);
+        let [h00, h10, h01, h11] = self.corners[self.idx(tx, tz)];
+        let near = h00 + (h10 - h00) * u;
+        let far = h01 + (h11 - h01) * u;
+        near + (far - near) * v
+    }
+
+    /// Distance along `ray` to the first surface point, searched up to
+    /// `max_distance`. Marches in half-tile steps from where the ray drops
+    /// below the highest tile corner and refines the crossing by bisection,
+    /// so cliff walls are hit where they stand. Off the map the surface
+    /// continues at the nearest edge tile's height.
+    pub fn raycast(&self, ray: &Ray, max_distance: f32) -> Option<f32> {
+        const REFINE_STEPS: u32 = 16;
+        let above = |t: f32| {
+            let p = ray.at(t);
+            p.y > self.height_at(p.x, p.z)
+        };
+        if !above(0.0) {
+            return Some(0.0);
+        }
+        let top = self.max_height;
+        let mut t = if ray.origin.y > top { ray.intersect_plane(top)? } else { 0.0 };
+        let step = self.tile_size * 0.5;
+        while t < max_distance {
+            let next = (t + step).min(max_distance);
+            if !above(next) {
+                let (mut lo, mut hi) = (t, next);
+                for _ in 0..REFINE_STEPS {
+                    let mid = 0.5 * (lo + hi);
+                    if above(mid) { lo = mid } else { hi = mid }
+                }
+                return Some(hi);
+            }
+            t = next;
+        }
+        None
+    }
+
+    /// Write per-tile movement costs (ground type, creep) into `grid` and mark
+    /// the high side of every cliff unwalkable. The grid must have the same
+    /// dimensions as the terrain.
+    pub fn bake_navigation(&self, grid: &mut NavigationGrid) {
+        assert_eq!((grid.width, grid.height), (self.width, self.height), "terrain and navigation grid sizes differ");
+        for z in 0..self.height {
+            for x in 0..self.width {
+                let i = self.idx(x, z);
+                grid.cost[i] = self.types[i].nav_cost() * if self.creep[i] { CREEP_COST } else { 1.0 };
+
+                let neighbours = [
+                    (x + 1 < self.width).then(|| (x + 1, z)),
+                    x.checked_sub(1).map(|nx| (nx, z)),
+                    (z + 1 < self.height).then(|| (x, z + 1)),
+                    z.checked_sub(1).map(|nz| (x, nz)),
+                ];
+                let on_cliff_top = neighbours.into_iter().flatten().any(|(nx, nz)| {
+                    let (mine, theirs) = self.shared_edge(x, z, nx, nz);
+                    is_cliff(mine, theirs) && mine[0] + mine[1] > theirs[0] + theirs[1]
+                });
+                if on_cliff_top {
+                    grid.walkable[i] = false;
+                }
+            }
+        }
+    }
+
+    /// Splat layers for the renderer: dirt, rock and creep coverage in R, G, B
+    /// with the given layer colors (linear RGB, same order).
+    pub fn splat_map(&self, layer_colors: [[f32; 3]; 3]) -> SplatMap {
+        let weights = self.types.iter().zip(&self.creep).map(|(&terrain_type, &creep)| {
+            let full = |on: bool| if on { 255 } else { 0 };
+            [
+                full(terrain_type == TerrainType::Dirt),
+                full(terrain_type == TerrainType::Rock),
+                full(creep),
+                0,
+            ]
+        }).collect();
+        SplatMap {
+            width: self.width,
+            height: self.height,
+            origin: self.origin,
+            tile_size: self.tile_size,
+            weights,
+            layer_colors,
+        }
+    }
+
+    /// One mesh per CHUNK_TILES × CHUNK_TILES block of tiles, in row-major
+    /// chunk order. Tile tops carry flat per-tile normals; cliff walls
+    /// (including drops at the map border down to height 0) face the low side.
+    pub fn chunk_meshes(&self) -> Vec<RenderMesh> {
+        let mut chunks = Vec::new();
+        for cz in (0..self.height).step_by(CHUNK_TILES as usize) {
+            for cx in (0..self.width).step_by(CHUNK_TILES as usize) {
+                let mut mesh = RenderMesh { vertices: Vec::new(), indices: Vec::new() };
+                for z in cz..(cz + CHUNK_TILES).min(self.height) {
+                    for x in cx..(cx + CHUNK_TILES).min(self.width) {
+                        self.push_tile(&mut mesh, x, z);
+                    }
+                }
+                chunks.push(mesh);
+            }
+        }
+        chunks
+    }
+
+    fn idx(&self, x: u32, z: u32) -> usize {
+        (z * self.width + x) as usize
+    }
+
+    /// World position of tile corner (x, z) at `h`.
+    fn corner_pos(&self, x: u32, z: u32, h: f32) -> Vec3 {
+        Vec3::new(
+            self.origin.x + x as f32 * self.tile_size,
+            h,
+            self.origin.y + z as f32 * self.tile_size,
+        )
+    }
+
+    /// Heights of the edge between two adjacent tiles, as seen from each,
+    /// ordered along the edge by increasing X or Z.
+    fn shared_edge(&self, x: u32, z: u32, nx: u32, nz: u32) -> ([f32; 2], [f32; 2]) {
+        let a = self.corners[self.idx(x, z)];
+        let b = self.corners[self.idx(nx, nz)];
+        match (nx.cmp(&x), nz.cmp(&z)) {
+            (std::cmp::Ordering::Greater, _) => ([a[1], a[3]], [b[0], b[2]]),
+            (std::cmp::Ordering::Less, _) => ([a[0], a[2]], [b[1], b[3]]),
+            (_, std::cmp::Ordering::Greater) => ([a[2], a[3]], [b[0], b[1]]),
+            _ => ([a[0], a[1]], [b[2], b[3]]),
+        }
+    }
+
+    fn push_tile(&self, mesh: &mut RenderMesh, x: u32, z: u32) {
+        let [h00, h10, h01, h11] = self.corners[self.idx(x, z)];
+        let p00 = self.corner_pos(x, z, h00);
+        let p10 = self.corner_pos(x + 1, z, h10);
+        let p01 = self.corner_pos(x, z + 1, h01);
+        let p11 = self.corner_pos(x + 1, z + 1, h11);
+        let normal = ((p01 - p00).cross(p11 - p00) + (p11 - p00).cross(p10 - p00)).normalize();
+        push_quad(mesh, [p00, p01, p11, p10], normal);
+
+        // Walls on the +X and +Z edges are shared with the neighbour, so each
+        // is emitted once; the -X/-Z edges only on the map border.
+        let edges = [
+            (x + 1, z, x + 1, [h10, h11], Vec3::X),
+            (x, z + 1, z + 1, [h01, h11], Vec3::Z),
+        ];
+        for (nx, nz, line, mine, axis) in edges {
+            let theirs = if nx < self.width && nz < self.height {
+                self.shared_edge(x, z, nx, nz).1
+            } else {
+                [0.0; 2]
+            };
+            self.push_wall(mesh, axis, line, if axis == Vec3::X { z } else { x }, mine, theirs);
+        }
+        if x == 0 {
+            self.push_wall(mesh, Vec3::X, 0, z, [0.0; 2], [h00, h01]);
+        }
+        if z == 0 {
+            self.push_wall(mesh, Vec3::Z, 0, x, [0.0; 2], [h00, h10]);
+        }
+    }
+
+    /// Wall along the tile edge at `line` on `axis` (X: the edge x = line,
+    /// running from z = start to start + 1; Z: vice versa). `before` and
+    /// `after` are the edge heights on the low- and high-coordinate sides.
+    fn push_wall(&self, mesh: &mut RenderMesh, axis: Vec3, line: u32, start: u32, before: [f32; 2], after: [f32; 2]) {
+        if !is_cliff(before, after) {
+            return;
+        }
+        let (a, b) = if axis == Vec3::X { ((line, start), (line, start + 1)) } else { ((start, line), (start + 1, line)) };
+        let top = [before[0].max(after[0]), before[1].max(after[1])];
+        let bottom = [before[0].min(after[0]), before[1].min(after[1])];
+        let faces_after = before[0] + before[1] > after[0] + after[1];
+        push_quad(
+            mesh,
+            [
+                self.corner_pos(a.0, a.1, bottom[0]),
+                self.corner_pos(b.0, b.1, bottom[1]),
+                self.corner_pos(b.0, b.1, top[1]),
+                self.corner_pos(a.0, a.1, top[0]),
+            ],
+            if faces_after { axis } else { -axis },
+        );
+    }
+}
+
+fn is_cliff(a: [f32; 2], b: [f32; 2]) -> bool {
+    (a[0] - b[0]).abs() > CLIFF_EPSILON || (a[1] - b[1]).abs() > CLIFF_EPSILON
+}
+
+/// Append a quad with its own four vertices, wound counter-clockwise as seen
+/// from the side `normal` points to.
+fn push_quad(mesh: &mut RenderMesh, corners: [Vec3; 4], normal: Vec3) {
+    let base = mesh.vertices.len() as u32;
+    mesh.vertices.extend(corners.iter().map(|p| GpuVertex { position: p.to_array(), normal: normal.to_array() }));
+    let facing = (corners[1] - corners[0]).cross(corners[2] - corners[0]).dot(normal) >= 0.0;
+    let order: [u32; 6] = if facing { [0, 1, 2, 0, 2, 3] } else { [0, 2, 1, 0, 3, 2] };
+    mesh.indices.extend(order.iter().map(|i| base + i));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 8×8 unit tiles from the origin: flat at 0 up to x = 2, a ramp rising
+    /// to 2 at x = 6, then a plateau at 2. Height is (x - 2) / 2 on the ramp.
+    fn ramp_terrain() -> Terrain {
+        let mut terrain = Terrain::new(8, 8, Vec2::ZERO, 1.0);
+        terrain.set_level(6..8, 0..8, 2.0);
+        terrain.ramp(2..6, 0..8, RampDirection::PosX, 0.0, 2.0);
+        terrain
+    }
+
+    fn assert_hit(terrain: &Terrain, ray: Ray, expected: Vec3) {
+        let t = terrain.raycast(&ray, 100.0).expect("ray should hit the terrain");
+        assert!(ray.at(t).distance(expected) < 1e-3, "hit {} instead of {expected}", ray.at(t));
+    }
+
+    #[test]
+    fn raycast_hits_ramp_from_above() {
+        let terrain = ramp_terrain();
+        assert_hit(&terrain, Ray::new(Vec3::new(4.0, 10.0, 4.0), Vec3::NEG_Y), Vec3::new(4.0, 1.0, 4.0));
+        // Diagonal descent y = 5 - x meets (x - 2) / 2 at x = 4.
+        assert_hit(&terrain, Ray::new(Vec3::new(0.0, 5.0, 4.0), Vec3::new(1.0, -1.0, 0.0)), Vec3::new(4.0, 1.0, 4.0));
+    }
+
+    #[test]
+    fn raycast_starts_below_the_highest_corner() {
+        // Level flight at y = 1 crosses the ramp where it reaches that height.
+        let terrain = ramp_terrain();
+        assert_hit(&terrain, Ray::new(Vec3::new(0.5, 1.0, 4.0), Vec3::X), Vec3::new(4.0, 1.0, 4.0));
+    }
+
+    #[test]
+    fn raycast_misses_upward_and_out_of_range() {
+        let terrain = ramp_terrain();
+        assert_eq!(terrain.raycast(&Ray::new(Vec3::new(4.0, 3.0, 4.0), Vec3::Y), 100.0), None);
+        assert_eq!(terrain.raycast(&Ray::new(Vec3::new(4.0, 10.0, 4.0), Vec3::NEG_Y), 5.0), None);
+    }
+
+    #[test]
+    fn max_height_follows_edits() {
+        let mut terrain = ramp_terrain();
+        assert_eq!(terrain.max_height, 2.0);
+        terrain.set_level(2..8, 0..8, 0.5);
+        assert_eq!(terrain.max_height, 0.5);
+    }
+}
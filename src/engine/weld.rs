@@ -0,0 +1,214 @@
+// Geometry welding: merge coincident vertices in a PolyMesh.
+// See docs/research/procedural-modeling.md for where this sits in the pipeline.
+//
+// Skinning several nodes or combining primitives leaves duplicate vertices at
+// shared corners. Duplicates split the mesh into disconnected islands: smooth
+// normals stop averaging across the seam and Catmull-Clark treats each side as
+// a boundary. Welding runs between skin_modifier() and subdivide():
+//
+//   SkinGraph → skin_modifier() → weld() → catmull_clark() → ...
+
+use std::collections::HashMap;
+use glam::{IVec3, Vec3};
+use super::mesh::PolyMesh;
+
+// ============================================================================
+// REPORT
+// ============================================================================
+
+/// Summary of what `weld()` changed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeldReport {
+    /// Vertices folded into an earlier vertex within tolerance.
+    pub vertices_merged: usize,
+    /// Vertices dropped because no surviving face referenced them.
+    pub vertices_unreferenced: usize,
+    /// Faces dropped for having fewer than 3 distinct vertices after remapping,
+    /// or for repeating the vertex set of another face.
+    pub faces_dropped: usize,
+}
+
+impl WeldReport {
+    /// True if the weld pass left the mesh unchanged.
+    pub fn is_noop(&self) -> bool {
+        self.vertices_merged == 0 && self.vertices_unreferenced == 0 && self.faces_dropped == 0
+    }
+}
+
+// ============================================================================
+// PUBLIC API
+// ============================================================================
+
+/// Merge vertices closer than `tolerance`, remap faces, and drop degenerate faces.
+///
+/// The first vertex (by index) in each cluster is kept as the representative so
+/// the result is deterministic. Within a face, consecutive indices that collapse
+/// onto the same vertex are removed; faces left with fewer than 3 vertices are
+/// dropped. Faces over the same vertex set as an earlier face are dropped too:
+/// a same-winding repeat is a duplicate, while an opposite-winding pair is the
+/// internal wall between two welded parts and both sides go. Vertices no longer
+/// referenced by any face are compacted away.
+///
+/// Uses a uniform hash grid with cell size = tolerance, so each lookup checks
+/// at most 27 cells.
+pub fn weld(mesh: &PolyMesh, tolerance: f32) -> (PolyMesh, WeldReport) {
+    let mut report = WeldReport::default();
+    let tol = tolerance.max(f32::EPSILON);
+    let tol_sq = tol * tol;
+    let cell_of = |p: Vec3| (p / tol).floor().as_ivec3();
+
+    // ---- Pass 1: cluster vertices --------------------------------------------
+    // representative[v] = index (into mesh.positions) of the vertex v merges into.
+    let mut grid: HashMap<IVec3, Vec<usize>> = HashMap::new();
+    let mut representative: Vec<usize> = Vec::with_capacity(mesh.vertex_count());
+
+    for (vi, &p) in mesh.positions.iter().enumerate() {
+        let cell = cell_of(p);
+        let mut found = None;
+        'search: for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let Some(bucket) = grid.get(&(cell + IVec3::new(dx, dy, dz))) else { continue };
+                    for &rep in bucket {
+                        if mesh.positions[rep].distance_squared(p) <= tol_sq {
+                            found = Some(rep);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+        match found {
+            Some(rep) => {
+                representative.push(rep);
+                report.vertices_merged += 1;
+            }
+            None => {
+                representative.push(vi);
+                grid.entry(cell).or_default().push(vi);
+            }
+        }
+    }
+
+    // ---- Pass 2: remap faces, drop degenerates -------------------------------
+    let mut faces: Vec<Vec<usize>> = Vec::with_capacity(mesh.faces.len());
+    for face in &mesh.faces {
+        let mut remapped: Vec<usize> = Vec::with_capacity(face.len());
+        for &v in face {
+            let r = representative[v];
+            if remapped.last() != Some(&r) {
+                remapped.push(r);
+            }
+        }
+        // The face wraps around: last and first may have collapsed together.
+        while remapped.len() > 1 && remapped.first() == remapped.last() {
+            remapped.pop();
+        }
+        if remapped.len() < 3 {
+            report.faces_dropped += 1;
+        } else {
+            faces.push(remapped);
+        }
+    }
+
+    // ---- Pass 2b: drop faces that repeat a vertex set -----------------------
+    // earlier[set] = kept faces over that vertex set (all sharing one winding).
+    let mut earlier: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+    let mut keep = vec![true; faces.len()];
+    for (fi, face) in faces.iter().enumerate() {
+        let mut key = face.clone();
+        key.sort_unstable();
+        let same_set = earlier.entry(key).or_default();
+        if let Some(pos) = same_set.iter().position(|&g| is_reversed(&faces[g], face)) {
+            keep[same_set.swap_remove(pos)] = false;
+            keep[fi] = false;
+            report.faces_dropped += 2;
+        } else if !same_set.is_empty() {
+            keep[fi] = false;
+            report.faces_dropped += 1;
+        } else {
+            same_set.push(fi);
+        }
+    }
+    let faces: Vec<Vec<usize>> = faces.into_iter().zip(keep)
+        .filter_map(|(face, kept)| kept.then_some(face))
+        .collect();
+
+    // ---- Pass 3: compact referenced vertices (original order preserved) -----
+    let mut referenced = vec![false; mesh.vertex_count()];
+    for face in &faces {
+        for &v in face { referenced[v] = true; }
+    }
+    let mut out = PolyMesh::new();
+    let new_index: Vec<usize> = mesh.positions.iter().zip(referenced.iter())
+        .map(|(&p, &used)| if used { out.add_vertex(p) } else { usize::MAX })
+        .collect();
+    for face in faces {
+        out.add_face(face.iter().map(|&v| new_index[v]).collect());
+    }
+
+    let representatives = mesh.vertex_count() - report.vertices_merged;
+    report.vertices_unreferenced = representatives - out.vertex_count();
+
+    (out, report)
+}
+
+/// True if `b` visits the same cycle of vertices as `a` in the opposite direction.
+fn is_reversed(a: &[usize], b: &[usize]) -> bool {
+    let n = a.len();
+    if n != b.len() {
+        return false;
+    }
+    let Some(start) = b.iter().position(|&v| v == a[0]) else { return false };
+    (0..n).all(|i| a[i] == b[(start + n - i) % n])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use glam::Vec3;
+    use super::*;
+    use super::super::skin::{SkinGraph, skin_modifier};
+
+    /// Every directed edge appears exactly once and its reverse exactly once.
+    fn is_closed_manifold(mesh: &PolyMesh) -> bool {
+        let mut directed: HashMap<(usize, usize), u32> = HashMap::new();
+        for face in &mesh.faces {
+            for (i, &a) in face.iter().enumerate() {
+                *directed.entry((a, face[(i + 1) % face.len()])).or_default() += 1;
+            }
+        }
+        directed.iter().all(|(&(a, b), &n)| n == 1 && directed.get(&(b, a)) == Some(&1))
+    }
+
+    #[test]
+    fn touching_cubes_weld_into_one_closed_box() {
+        let mut graph = SkinGraph::new();
+        graph.add_node(Vec3::ZERO, 0.5);
+        graph.add_node(Vec3::new(1.0, 0.0, 0.0), 0.5);
+        let (welded, report) = weld(&skin_modifier(&graph), 1e-4);
+
+        assert_eq!(report.vertices_merged, 4);
+        // The two faces of the shared wall cancel out.
+        assert_eq!(report.faces_dropped, 2);
+        assert_eq!((welded.vertex_count(), welded.faces.len()), (12, 10));
+        assert!(is_closed_manifold(&welded));
+    }
+
+    #[test]
+    fn duplicate_faces_are_dropped() {
+        let mut mesh = PolyMesh::new();
+        for p in [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z] {
+            mesh.add_vertex(p);
+        }
+        // Tetrahedron with one face repeated, starting from another corner.
+        for face in [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2], [2, 1, 0]] {
+            mesh.add_face(face.to_vec());
+        }
+        let (welded, report) = weld(&mesh, 1e-4);
+
+        assert_eq!(report.faces_dropped, 1);
+        assert_eq!(welded.faces.len(), 4);
+        assert!(is_closed_manifold(&welded));
+    }
+}
@@ -0,0 +1,107 @@
+// In-world unit UI: selection rings under selected/hovered units and
+// health/shield bars above them.
+// See docs/research/rendering-architecture.md §"World-Space UI".
+//
+// This module decides which units get a ring or bar and how they look; the
+// renderer (renderer/world_ui.rs) batches all rings into one draw and all
+// bars into another.
+
+use bevy_ecs::prelude::*;
+use glam::Vec3;
+use super::components::{AttachmentSockets, Health, Hovered, Selected, SkinPose, Transform, UnitAgent, socket_world_matrix};
+use super::renderer::Renderer;
+
+/// Seconds a bar stays up after a hit under `HealthBarVisibility::OnDamage`.
+pub const DAMAGE_BAR_SECONDS: f32 = 3.0;
+
+/// Ring outer radius as a multiple of `UnitAgent::radius`.
+const RING_SCALE: f32 = 1.3;
+/// Rings sit this far above the ground to stay clear of z-fighting.
+const RING_LIFT: f32 = 0.02;
+/// Bar width as a multiple of `UnitAgent::radius`.
+const BAR_WIDTH_SCALE: f32 = 2.0;
+/// Bar center height above the "overhead" socket (or the unit's top).
+const BAR_LIFT: f32 = 0.35;
+
+const SELECTED_RING: [f32; 4] = [0.3, 1.0, 0.4, 0.9];
+const HOVERED_RING: [f32; 4] = [1.0, 1.0, 1.0, 0.45];
+
+/// Which units show a health bar. Selected and hovered units always do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthBarVisibility {
+    /// Every unit with `Health`.
+    Always,
+    /// Only selected and hovered units.
+    OnHover,
+    /// Also units hit within the last DAMAGE_BAR_SECONDS.
+    OnDamage,
+}
+
+impl HealthBarVisibility {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Always => Self::OnHover,
+            Self::OnHover => Self::OnDamage,
+            Self::OnDamage => Self::Always,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::OnHover => "on hover",
+            Self::OnDamage => "on damage",
+        }
+    }
+}
+
+/// Queue rings and bars for every unit that should show them this frame.
+pub fn submit_world_ui(world: &mut World, renderer: &mut Renderer, visibility: HealthBarVisibility) {
+    let mut query = world.query::<(
+        &Transform,
+        &UnitAgent,
+        Option<&Health>,
+        Option<(&AttachmentSockets, &SkinPose)>,
+        Has<Selected>,
+        Has<Hovered>,
+    )>();
+    for (transform, agent, health, sockets, selected, hovered) in query.iter(world) {
+        // Units rest on the terrain, so their feet are one radius below the center.
+        let ground = transform.position + Vec3::Y * (RING_LIFT - agent.radius);
+        if selected {
+            renderer.submit_selection_ring(ground, agent.radius * RING_SCALE, SELECTED_RING);
+        } else if hovered {
+            renderer.submit_selection_ring(ground, agent.radius * RING_SCALE, HOVERED_RING);
+        }
+
+        let Some(health) = health else { continue };
+        let show = selected || hovered || match visibility {
+            HealthBarVisibility::Always => true,
+            HealthBarVisibility::OnHover => false,
+            HealthBarVisibility::OnDamage => health.since_damage < DAMAGE_BAR_SECONDS,
+        };
+        if !show {
+            continue;
+        }
+
+        let top = sockets
+            .and_then(|(sockets, pose)| sockets.resolve(pose).find(|s| s.name == "overhead"))
+            .map_or(transform.position + Vec3::Y * agent.radius, |s| {
+                socket_world_matrix(transform, &s).w_axis.truncate()
+            });
+        let hp = health.hp_fraction();
+        renderer.submit_status_bar(
+            top + Vec3::Y * BAR_LIFT,
+            agent.radius * BAR_WIDTH_SCALE,
+            hp,
+            health.shield_fraction(),
+            health_color(hp),
+        );
+    }
+}
+
+/// Green at full health through yellow to red when nearly dead.
+fn health_color(fraction: f32) -> [f32; 4] {
+    let f = fraction.clamp(0.0, 1.0);
+    [(2.0 * (1.0 - f)).min(1.0), (2.0 * f).min(1.0), 0.1, 1.0]
+}
@@ -0,0 +1,121 @@
+// Image-diff regression tests for the renderer.
+//
+// The sphere scene (main.rs `load_sphere_scene`) is rendered headless on the
+// software adapter and compared against reference PNGs in tests/golden/.
+// Rasterizers differ in the last bit, so a pixel only counts as changed when
+// a channel moves by more than CHANNEL_TOLERANCE, and the test fails when
+// more than MAX_CHANGED_FRACTION of the pixels changed. The rendered image is
+// written to target/golden-diff/ on failure for inspection.
+//
+// Intentional rendering changes: regenerate the references with
+//   UPDATE_GOLDEN=1 cargo test golden
+// and look at the new PNGs before committing.
+//
+// Without a software adapter the tests fail, unless SKIP_GPU_GOLDEN is set, in
+// which case they print a note and pass (for machines with no GPU driver).
+
+use std::path::PathBuf;
+use crate::engine::renderer::{Image, RenderSettings, Renderer};
+use crate::{load_sphere_scene, SCREENSHOT_SIZE};
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+const DIFF_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/golden-diff");
+const CHANNEL_TOLERANCE: u8 = 8;
+const MAX_CHANGED_FRACTION: f32 = 0.002;
+/// Set to let the image tests pass on machines without a software adapter.
+const SKIP_ENV: &str = "SKIP_GPU_GOLDEN";
+
+/// Render the sphere scene with `settings`, or `None` without a software
+/// adapter when SKIP_ENV allows it.
+fn render_sphere_scene(settings: RenderSettings) -> Option<Image> {
+    let (width, height) = SCREENSHOT_SIZE;
+    let Some(mut renderer) = pollster::block_on(Renderer::new_headless(width, height)) else {
+        assert!(
+            std::env::var_os(SKIP_ENV).is_some(),
+            "no software adapter for the image-diff tests (set {SKIP_ENV}=1 to skip them)",
+        );
+        eprintln!("no software adapter; {SKIP_ENV} set, skipping image-diff test");
+        return None;
+    };
+    renderer.set_settings(settings);
+    load_sphere_scene(&mut renderer);
+    Some(renderer.render_to_image())
+}
+
+fn read_png(path: &PathBuf) -> Image {
+    let file = std::fs::File::open(path)
+        .unwrap_or_else(|_| panic!("missing {} — run UPDATE_GOLDEN=1 cargo test golden", path.display()));
+    let mut reader = png::Decoder::new(file).read_info().expect("decode golden PNG");
+    let mut rgba = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut rgba).expect("decode golden PNG");
+    assert_eq!(info.color_type, png::ColorType::Rgba, "golden PNGs are RGBA8");
+    rgba.truncate(info.buffer_size());
+    Image { width: info.width, height: info.height, rgba }
+}
+
+/// Pixels with any channel further than CHANNEL_TOLERANCE apart.
+fn changed_pixels(a: &Image, b: &Image) -> usize {
+    a.rgba.chunks(4).zip(b.rgba.chunks(4))
+        .filter(|(p, q)| p.iter().zip(q.iter()).any(|(x, y)| x.abs_diff(*y) > CHANNEL_TOLERANCE))
+        .count()
+}
+
+fn assert_matches_golden(name: &str, actual: &Image) {
+    let path = PathBuf::from(GOLDEN_DIR).join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.write_png(&path).expect("write golden PNG");
+        return;
+    }
+
+    let expected = read_png(&path);
+    assert_eq!(
+        (actual.width, actual.height), (expected.width, expected.height),
+        "{name}: image size changed",
+    );
+
+    let changed = changed_pixels(actual, &expected);
+    let total = (actual.width * actual.height) as usize;
+    if changed as f32 > total as f32 * MAX_CHANGED_FRACTION {
+        std::fs::create_dir_all(DIFF_DIR).expect("create diff dir");
+        let diff_path = PathBuf::from(DIFF_DIR).join(format!("{name}.png"));
+        actual.write_png(&diff_path).expect("write actual PNG");
+        panic!(
+            "{name}: {changed} of {total} pixels differ from the golden image (actual written to {})",
+            diff_path.display(),
+        );
+    }
+}
+
+#[test]
+fn golden_sphere_scene() {
+    if let Some(image) = render_sphere_scene(RenderSettings::default()) {
+        assert_matches_golden("sphere_scene", &image);
+    }
+}
+
+/// Raw scene pass: no MSAA, no post-processing. Separates scene-shader
+/// regressions from post-processing ones.
+#[test]
+fn golden_sphere_scene_unprocessed() {
+    let settings = RenderSettings {
+        msaa_samples: 1,
+        bloom: false,
+        tone_mapping: false,
+        color_grading: false,
+        ..RenderSettings::default()
+    };
+    if let Some(image) = render_sphere_scene(settings) {
+        assert_matches_golden("sphere_scene_unprocessed", &image);
+    }
+}
+
+#[test]
+fn changed_pixels_respects_tolerance() {
+    let base = Image { width: 2, height: 1, rgba: vec![100, 100, 100, 255, 10, 20, 30, 255] };
+    let mut near = Image { rgba: base.rgba.clone(), ..base };
+    near.rgba[0] += CHANNEL_TOLERANCE;
+    assert_eq!(changed_pixels(&base, &near), 0);
+    near.rgba[6] += CHANNEL_TOLERANCE + 1;
+    assert_eq!(changed_pixels(&base, &near), 1);
+}
@@ -2,6 +2,8 @@
 // See docs/research/procedural-modeling.md for pipeline decisions.
 
 mod engine;
+#[cfg(test)]
+mod golden_render;
 
 use winit::{
     application::ApplicationHandler,
@@ -13,18 +15,90 @@ use winit::{
 use glam::{Mat4, Vec3};
 use bevy_ecs::prelude::*;
 use engine::{Transform, Color as EntityColor, Velocity, GroupMembership, UnitAgent, FormationOffset};
+use engine::{AttachmentSockets, SkinPose, MeshHandle, MaterialHandle, Emissive, PointLightEmitter};
+use engine::{Health, Hovered, Selected};
+use engine::renderer::{DecalShape, Image, LightUniform, Material, ParticleBlend, ParticleInstance, PointLight, Renderer};
+use engine::decals::{Decal, DecalId, Decals};
+use engine::particles::{Curve, ParticleEffect, ParticleEmitter, ParticleSystem};
+use engine::world_ui::{HealthBarVisibility, submit_world_ui};
 use engine::{NavigationGrid, compute_astar, GRID_WIDTH, GRID_HEIGHT};
 use engine::{AgentSnapshot, SpatialGrid, compute_orca_velocity};
-use engine::camera::RtsCamera;
-use engine::debug_overlay::{DebugOverlay, DebugStats, UnitDebugDraw};
+use engine::camera::{CameraView, RtsCamera};
+use engine::event_log::EventLog;
+use engine::picking::pick_nearest;
+use engine::terrain::{RampDirection, Terrain, TerrainType};
+use engine::systems::socket_world_transforms;
+use engine::debug_overlay::{DebugLayers, DebugOverlay, DebugStats, UnitDebugDraw};
 use engine::input::InputState;
-use engine::mesh::GpuVertex;
+use engine::mesh::ContentHash;
 use egui;
 
+/// Energy-core glow: emissive strength of the unit body and reach of its point light.
+const CORE_EMISSIVE: f32 = 0.35;
+const CORE_LIGHT_RADIUS: f32 = 3.0;
+
+/// Unit durability: every unit has hp; energy-core units add a shield.
+const UNIT_MAX_HP: f32 = 100.0;
+const CORE_MAX_SHIELD: f32 = 50.0;
+/// Shields start recharging this long after the last hit, at this rate per second.
+const SHIELD_REGEN_DELAY: f32 = 2.0;
+const SHIELD_REGEN_RATE: f32 = 10.0;
+/// Damage dealt to selected units by the K debug key.
+const DEBUG_DAMAGE: f32 = 15.0;
+
+/// Seed of the first emitter's RNG; later emitters count up from here.
+const PARTICLE_SEED: u64 = 0x5EED;
+
+/// Splat layer colors (linear RGB) painted over the grass ground: dirt, rock, creep.
+const TERRAIN_LAYER_COLORS: [[f32; 3]; 3] = [
+    [0.40, 0.29, 0.17],
+    [0.36, 0.35, 0.33],
+    [0.33, 0.10, 0.38],
+];
+
+/// Scorch marks left under units hit by the K debug key: radius, seconds on
+/// the ground, and how many of those seconds they spend fading out.
+const SCORCH_RADIUS: f32 = 1.2;
+const SCORCH_SECONDS: f32 = 8.0;
+const SCORCH_FADE: f32 = 3.0;
+const SCORCH_COLOR: [f32; 4] = [0.02, 0.015, 0.01, 0.85];
+
+/// Rally marks under each group's goal: radius and opacity (color is the group's).
+const RALLY_RADIUS: f32 = 2.0;
+const RALLY_ALPHA: f32 = 0.8;
+
+/// Building placement preview (B): footprint edge in tiles, and its tint
+/// when the site is buildable (walkable and flat) or blocked.
+const FOOTPRINT_TILES: u32 = 4;
+const FOOTPRINT_VALID: [f32; 4] = [0.2, 1.0, 0.3, 0.6];
+const FOOTPRINT_BLOCKED: [f32; 4] = [1.0, 0.15, 0.1, 0.6];
+
+/// Unit style: cel-shaded with a dark inverted-hull outline for clean silhouettes.
+const UNIT_OUTLINE_WIDTH: f32 = 0.05;
+const UNIT_OUTLINE_COLOR: [f32; 3] = [0.02, 0.02, 0.03];
+
+/// Camera bookmark slots: Ctrl+key stores the current view, the key recalls
+/// it. Every free key from F1 to F9: F3/F4 are taken by the debug overlay and
+/// F5 is reserved for the flowfield view. F10–F12 stay unbound since desktops
+/// commonly claim them (menu bar, fullscreen, developer tools).
+const BOOKMARK_KEYS: [KeyCode; 6] = [KeyCode::F1, KeyCode::F2, KeyCode::F6, KeyCode::F7, KeyCode::F8, KeyCode::F9];
+
+/// Camera pitch at full zoom-in: flatter than the default 55° overview, so
+/// close-ups look across the battlefield.
+const CAMERA_ZOOMED_IN_PITCH_DEG: f32 = 38.0;
+
+/// Camera distance beyond which sphere units switch to their coarser LOD.
+const SPHERE_LOD_DISTANCE: f32 = 45.0;
+
+/// Size of `--screenshot` images and of the golden renders in golden_render.rs.
+const SCREENSHOT_SIZE: (u32, u32) = (320, 240);
+
 /// Movement speed for all units (world units per second).
 const UNIT_SPEED: f32 = 2.5;
 /// Physical collision radius of each unit (matches the procedural sphere mesh).
 const UNIT_RADIUS: f32 = 0.5;
+/// How quickly units turn to face their velocity (fraction of the remaining angle per second).
+const UNIT_TURN_RATE: f32 = 8.0;
 /// Units within this world-space distance of their goal are considered arrived.
 const ARRIVAL_RADIUS: f32 = 1.5;
 /// ORCA look-ahead window (seconds).  Shorter = more direct movement; longer = smoother lanes.
@@ -64,88 +138,11 @@ const REFORM_WAIT_TRIGGER: f32 = 3.0;
 /// 2× normal speed means a unit 4–5 units behind its slot closes the gap in under one second.
 const CATCHUP_SPEED_MULT: f32 = 2.0;
 
-// ============================================================================
-// INSTANCE DATA (per-entity, passed alongside the shared procedural mesh)
-// ============================================================================
-
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct InstanceData {
-    position: [f32; 3],
-    _padding: f32,  // Align to 16 bytes
-    color: [f32; 4],
-}
-
-impl InstanceData {
-    fn desc() -> wgpu::VertexBufferLayout<'static> {
-        wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Instance,
-            attributes: &[
-                // Position (location 2, after vertex position and normal)
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Float32x3,
-                },
-                // Color (location 3)
-                wgpu::VertexAttribute {
-                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Float32x4,
-                },
-            ],
-        }
-    }
-}
-
-// ============================================================================
-// UNIFORM DATA (camera and lighting)
-// ============================================================================
-
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct Uniforms {
-    view_proj: [[f32; 4]; 4],
-    camera_pos: [f32; 3],
-    _padding: u32,
-}
-
-impl Uniforms {
-    fn new() -> Self {
-        Self {
-            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
-            camera_pos: [0.0, 0.0, 0.0],
-            _padding: 0,
-        }
-    }
-}
-
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct LightUniform {
-    direction: [f32; 3],
-    _padding: u32,
-    color: [f32; 3],
-    _padding2: u32,
-}
-
-impl LightUniform {
-    fn new() -> Self {
-        Self {
-            direction: [-0.3, -0.5, -0.6],
-            _padding: 0,
-            color: [1.0, 1.0, 1.0],
-            _padding2: 0,
-        }
-    }
-}
-
 // ============================================================================
 // PROCEDURAL MESH PIPELINE
 // ============================================================================
 
-/// Build a flat ground plane quad covering the full map area.
+/// Build a flat ground plane quad centred on the origin.
 /// Vertices are at y=0 with an upward normal so they lit from above.
 fn build_ground_plane_mesh(half_x: f32, half_z: f32) -> engine::mesh::RenderMesh {
     use engine::mesh::{GpuVertex, RenderMesh};
@@ -161,25 +158,122 @@ fn build_ground_plane_mesh(half_x: f32, half_z: f32) -> engine::mesh::RenderMesh
     RenderMesh { vertices, indices }
 }
 
-/// Build the test mesh: single vertex → Skin Modifier (cube) → Catmull-Clark ×2.
-/// Returns a GPU-ready RenderMesh with smooth normals (98 verts, 576 indices).
-fn build_procedural_sphere() -> engine::mesh::RenderMesh {
-    use engine::{SkinGraph, skin_modifier, subdivide, triangulate_smooth};
+/// Battlefield over the navigation grid. A north–south ridge splits the map,
+/// crossed by a dirt ramp on each side in the middle; a dirt track runs along
+/// the crossing lane; rocky mesas, each reached by its own ramp, sit in the two
+/// corners off the lane, with rock outcrops and creep patches on the flanks.
+fn build_battlefield_terrain() -> Terrain {
+    use engine::navigation::{CELL_SIZE, WORLD_HALF};
+    const RIDGE: f32 = 1.5;
+    const MESA: f32 = 3.0;
+    let c = GRID_WIDTH / 2;
+    let mut terrain = Terrain::new(GRID_WIDTH, GRID_HEIGHT, glam::Vec2::splat(-WORLD_HALF), CELL_SIZE);
+
+    // Dirt track along the NW–SE diagonal between the two group starts.
+    terrain.paint_where(TerrainType::Dirt, |x, z| {
+        x.abs_diff(z) <= 2 && (c - 36..c + 36).contains(&x)
+    });
+
+    terrain.set_level(c - 6..c + 6, 0..GRID_HEIGHT, RIDGE);
+    terrain.ramp(c - 12..c - 6, c - 20..c + 20, RampDirection::PosX, 0.0, RIDGE);
+    terrain.ramp(c + 6..c + 12, c - 20..c + 20, RampDirection::NegX, 0.0, RIDGE);
+    terrain.paint(c - 12..c - 6, c - 20..c + 20, TerrainType::Dirt);
+    terrain.paint(c + 6..c + 12, c - 20..c + 20, TerrainType::Dirt);
+
+    terrain.set_level(c + 20..c + 38, c - 42..c - 24, MESA);
+    terrain.ramp(c + 24..c + 34, c - 48..c - 42, RampDirection::PosZ, 0.0, MESA);
+    terrain.paint(c + 20..c + 38, c - 42..c - 24, TerrainType::Rock);
+    terrain.set_level(c - 38..c - 20, c + 24..c + 42, MESA);
+    terrain.ramp(c - 34..c - 24, c + 42..c + 48, RampDirection::NegZ, 0.0, MESA);
+    terrain.paint(c - 38..c - 20, c + 24..c + 42, TerrainType::Rock);
+
+    terrain.paint(c - 52..c - 42, c - 10..c + 2, TerrainType::Rock);
+    terrain.paint(c + 42..c + 54, c - 2..c + 10, TerrainType::Rock);
+    terrain.spread_creep(c - 40, c + 6, 9.0);
+    terrain.spread_creep(c + 40, c - 10, 9.0);
+    terrain
+}
+
+/// Unit skin graph: single vertex at origin, radius 0.5, with an "overhead"
+/// socket on top of the skin (anchor for selection/health UI and effects).
+fn build_unit_skin_graph() -> engine::SkinGraph {
+    let mut graph = engine::SkinGraph::new();
+    let body = graph.add_node(Vec3::ZERO, 0.5);
+    graph.add_socket(body, "overhead", Vec3::new(0.0, 0.5, 0.0), glam::Quat::IDENTITY);
+    graph
+}
+
+/// Build the test mesh: single vertex → Skin Modifier (cube) → Catmull-Clark × `levels`.
+/// Returns a GPU-ready RenderMesh on the limit surface (98 verts, 576 indices at 2 levels).
+fn build_procedural_sphere(graph: &engine::SkinGraph, levels: u32) -> engine::mesh::RenderMesh {
+    use engine::{skin_modifier, subdivide, triangulate_limit, weld};
+
+    // Step 1: Skin Modifier → cube (8 verts, 6 quad faces)
+    let skin_mesh = skin_modifier(graph);
+
+    // Step 2: Weld coincident vertices so subdivision sees one connected surface
+    let (cube_mesh, weld_report) = weld(&skin_mesh, 1e-4);
+    if !weld_report.is_noop() {
+        println!(
+            "Unit mesh weld: {} verts merged, {} unreferenced, {} faces dropped",
+            weld_report.vertices_merged,
+            weld_report.vertices_unreferenced,
+            weld_report.faces_dropped,
+        );
+    }
+
+    // Step 3: Catmull-Clark
+    let subd_mesh = subdivide(&cube_mesh, levels);
 
-    // Step 1: SkinGraph — single vertex at origin, radius 0.5
-    let mut graph = SkinGraph::new();
-    graph.add_node(Vec3::ZERO, 0.5);
+    // Step 4: Triangulate, pushing vertices onto the limit surface
+    triangulate_limit(&subd_mesh)
+}
 
-    // Step 2: Skin Modifier → cube (8 verts, 6 quad faces)
-    let cube_mesh = skin_modifier(&graph);
+/// Sparks shed by energy cores: short-lived, additive, falling under gravity.
+fn core_spark_effect() -> ParticleEffect {
+    ParticleEffect {
+        blend: ParticleBlend::Additive,
+        rate: 3.0,
+        burst: 0,
+        lifetime: (0.4, 0.8),
+        spawn_radius: 0.15,
+        velocity: Vec3::new(0.0, 1.2, 0.0),
+        velocity_spread: 0.8,
+        acceleration: Vec3::new(0.0, -4.0, 0.0),
+        drag: 0.5,
+        color: Curve::linear([3.0, 2.4, 1.2, 1.0], [1.5, 0.4, 0.1, 0.0]),
+        size: Curve::constant(0.1),
+    }
+}
 
-    // Step 3: Catmull-Clark ×2
-    let subd_mesh = subdivide(&cube_mesh, 2);
+/// Smoke rising from each group's goal: slow, alpha-blended, growing and fading.
+fn beacon_smoke_effect() -> ParticleEffect {
+    ParticleEffect {
+        blend: ParticleBlend::Alpha,
+        rate: 10.0,
+        burst: 0,
+        lifetime: (2.5, 3.5),
+        spawn_radius: 0.6,
+        velocity: Vec3::new(0.0, 1.0, 0.0),
+        velocity_spread: 0.3,
+        acceleration: Vec3::new(0.3, 0.4, 0.0),
+        drag: 0.3,
+        color: Curve::new(vec![
+            (0.0, [0.35, 0.35, 0.38, 0.0]),
+            (0.15, [0.35, 0.35, 0.38, 0.5]),
+            (1.0, [0.5, 0.5, 0.52, 0.0]),
+        ]),
+        size: Curve::linear(0.8, 2.5),
+    }
+}
 
-    // Step 4: Triangulate + smooth normals
-    let render_mesh = triangulate_smooth(&subd_mesh);
+/// Build the drone mesh: same skin graph, one Catmull-Clark level, cage normals.
+/// Reads as a rounded cube next to the sphere units (26 verts, 144 indices).
+fn build_drone_mesh(graph: &engine::SkinGraph) -> engine::mesh::RenderMesh {
+    use engine::{skin_modifier, subdivide, triangulate_smooth, weld};
 
-    render_mesh
+    let (cube_mesh, _) = weld(&skin_modifier(graph), 1e-4);
+    triangulate_smooth(&subdivide(&cube_mesh, 1))
 }
 
 // ============================================================================
@@ -197,6 +291,13 @@ struct UnitGroup {
     /// Each unit in the group steers toward `path[path_idx] + formation_offset`
     /// so every unit targets its own formation slot at each waypoint.
     path: Vec<glam::Vec2>,
+    /// Mesh and material every unit in this group renders with.
+    mesh: MeshHandle,
+    material: MaterialHandle,
+    /// Model-space scale applied to every unit in this group.
+    scale: Vec3,
+    /// Units carry a glowing energy core that lights their surroundings.
+    energy_core: bool,
     /// Order in which this group's move command was issued (0 = issued first).
     /// Copied onto every unit as its ORCA priority: lower value = higher rank =
     /// holds course (30% responsibility). Groups commanded later yield (70%).
@@ -234,18 +335,31 @@ impl GroupFormation {
 /// 0 = commanded first → highest ORCA priority (holds course, 30% responsibility).
 /// 1 = commanded second → yields, parts to make way (70% responsibility).
 /// Red is commanded first so Blue visibly parts around it.
-fn create_crossing_groups(nav_grid: &NavigationGrid) -> Vec<UnitGroup> {
-    // (start_xz, goal_xz, rgb, command_order)
-    let defs: &[([f32; 2], [f32; 2], [f32; 3], u32)] = &[
-        ([-35.0, -35.0], [ 35.0,  35.0], [1.00, 0.20, 0.20], 0), // NW→SE  Red   — commanded first
-        ([ 35.0,  35.0], [-35.0, -35.0], [0.20, 0.50, 1.00], 1), // SE→NW  Blue  — commanded second
+fn create_crossing_groups(nav_grid: &NavigationGrid, terrain: &Terrain, unit_types: &[(MeshHandle, MaterialHandle)]) -> Vec<UnitGroup> {
+    // (start_xz, goal_xz, rgb, command_order, unit_types index, scale, energy_core)
+    let defs: &[([f32; 2], [f32; 2], [f32; 3], u32, usize, [f32; 3], bool)] = &[
+        // NW→SE  Red spheres  — commanded first
+        ([-35.0, -35.0], [ 35.0,  35.0], [1.00, 0.20, 0.20], 0, 0, [1.0, 1.0, 1.0], false),
+        // SE→NW  Blue drones  — commanded second; elongated along -Z so heading reads clearly
+        ([ 35.0,  35.0], [-35.0, -35.0], [0.20, 0.50, 1.00], 1, 1, [0.7, 0.6, 1.0], true),
     ];
 
-    defs.iter().enumerate().map(|(i, (start_xz, goal_xz, color, command_order))| {
-        let start_world = glam::Vec3::new(start_xz[0], 0.5, start_xz[1]);
-        let goal_world  = glam::Vec3::new(goal_xz[0],  0.0, goal_xz[1]);
+    defs.iter().enumerate().map(|(i, (start_xz, goal_xz, color, command_order, unit_type, scale, energy_core))| {
+        let start_world = glam::Vec3::new(start_xz[0], terrain.height_at(start_xz[0], start_xz[1]) + UNIT_RADIUS, start_xz[1]);
+        let goal_world  = glam::Vec3::new(goal_xz[0],  terrain.height_at(goal_xz[0], goal_xz[1]), goal_xz[1]);
         let path        = compute_astar(nav_grid, start_world, goal_world);
-        UnitGroup { id: i as u32, color: *color, start_world, goal_world, path, command_order: *command_order }
+        UnitGroup {
+            id: i as u32,
+            color: *color,
+            start_world,
+            goal_world,
+            path,
+            mesh: unit_types[*unit_type].0,
+            material: unit_types[*unit_type].1,
+            scale: Vec3::from_array(*scale),
+            energy_core: *energy_core,
+            command_order: *command_order,
+        }
     }).collect()
 }
 
@@ -254,33 +368,36 @@ fn create_crossing_groups(nav_grid: &NavigationGrid) -> Vec<UnitGroup> {
 // ============================================================================
 
 struct State {
-    surface: wgpu::Surface<'static>,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
-    size: winit::dpi::PhysicalSize<u32>,
-    render_pipeline: wgpu::RenderPipeline,
-    vertex_buffer: wgpu::Buffer,
-    index_buffer: wgpu::Buffer,
-    instance_buffer: wgpu::Buffer,
-    num_indices: u32,
-    max_instances: usize,
-
-    // Ground plane
-    ground_vertex_buffer: wgpu::Buffer,
-    ground_index_buffer: wgpu::Buffer,
-    ground_instance_buffer: wgpu::Buffer,
-    ground_num_indices: u32,
-    uniform_buffer: wgpu::Buffer,
-    uniform_bind_group: wgpu::BindGroup,
-    light_bind_group: wgpu::BindGroup,
-    depth_texture: wgpu::Texture,
-    depth_view: wgpu::TextureView,
+    renderer: Renderer,
+
+    // Terrain: one mesh per chunk, over a flat apron that hides the map edge
+    terrain: Terrain,
+    terrain_chunks: Vec<MeshHandle>,
+    apron_mesh: MeshHandle,
+    ground_material: MaterialHandle,
+
+    // Translucent energy fields marking each group's destination
+    field_mesh: MeshHandle,
+    field_material: MaterialHandle,
 
     // ECS World
     world: World,
     last_update: std::time::Instant,
 
+    // Effects
+    particles: ParticleSystem,
+    decals: Decals,
+    /// B toggle: building footprint following the cursor.
+    placement_preview: Option<DecalId>,
+    /// Terrain point under the mouse cursor, if the cursor ray hits the ground.
+    cursor_ground: Option<Vec3>,
+    /// Saved views for BOOKMARK_KEYS, in the same order.
+    camera_bookmarks: [Option<CameraView>; BOOKMARK_KEYS.len()],
+    /// F toggle: unit the camera target is locked onto.
+    follow_target: Option<Entity>,
+    /// Recent events for Space to jump between.
+    events: EventLog,
+
     // Pathfinding
     nav_grid: NavigationGrid,
     groups: Vec<UnitGroup>,
@@ -301,6 +418,8 @@ struct State {
     debug_overlay: DebugOverlay,
     /// F4 toggle: draw avoidance-radius circles and velocity arrows per unit.
     debug_units_visible: bool,
+    /// H cycles which units show health bars.
+    health_bar_visibility: HealthBarVisibility,
     /// Milliseconds spent on the last A* computation pass.
     pathfinding_last_ms: f32,
     /// Running total of flowfield recomputes since startup.
@@ -308,260 +427,70 @@ struct State {
 }
 
 impl State {
-    fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> (wgpu::Texture, wgpu::TextureView) {
-        let size = wgpu::Extent3d {
-            width: config.width,
-            height: config.height,
-            depth_or_array_layers: 1,
-        };
-
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Depth Texture"),
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth32Float,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-            view_formats: &[],
-        });
-
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        (texture, view)
-    }
-
     async fn new(window: &std::sync::Arc<winit::window::Window>) -> Self {
         let size = window.inner_size();
-
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
-
-        let surface = instance.create_surface(window.clone()).unwrap();
-
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
-                    memory_hints: wgpu::MemoryHints::default(),
-                },
-                None,
-            )
-            .await
-            .unwrap();
-
-        let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .copied()
-            .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats[0]);
-
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes[0],
-            alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
-            desired_maximum_frame_latency: 2,
-        };
-        surface.configure(&device, &config);
-
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shader_instanced.wgsl").into()),
-        });
-
-        let uniforms = Uniforms::new();
-        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Uniform Buffer"),
-            contents: bytemuck::cast_slice(&[uniforms]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-
-        let uniform_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-                label: Some("uniform_bind_group_layout"),
-            });
-
-        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &uniform_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-            label: Some("uniform_bind_group"),
-        });
-
-        let light_uniform = LightUniform::new();
-        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Light Buffer"),
-            contents: bytemuck::cast_slice(&[light_uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-
-        let light_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-                label: Some("light_bind_group_layout"),
-            });
-
-        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &light_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: light_buffer.as_entire_binding(),
-            }],
-            label: Some("light_bind_group"),
-        });
-
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&uniform_bind_group_layout, &light_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                // GpuVertex has the same layout as the old Vertex (locations 0, 1)
-                buffers: &[GpuVertex::desc(), InstanceData::desc()],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
+        let mut renderer = Renderer::new(window.clone()).await;
+        renderer.set_light(LightUniform {
+            direction: [-0.3, -0.5, -0.6],
+            color: [1.0, 1.0, 1.0],
+            ..LightUniform::new()
         });
 
-        use wgpu::util::DeviceExt;
-
         // Generate the procedural sphere mesh (single vertex → skin → CC×2)
-        let render_mesh = build_procedural_sphere();
-
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Procedural Vertex Buffer"),
-            contents: render_mesh.vertex_bytes(),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Procedural Index Buffer"),
-            contents: render_mesh.index_bytes(),
-            usage: wgpu::BufferUsages::INDEX,
-        });
-
-        let num_indices = render_mesh.index_count() as u32;
-
-        // Instance buffer for per-entity position+color (shared across all entities)
-        let max_instances = 150;
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Instance Buffer"),
-            size: (max_instances * std::mem::size_of::<InstanceData>()) as u64,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let unit_graph = build_unit_skin_graph();
+        let render_mesh = build_procedural_sphere(&unit_graph, 2);
+        println!(
+            "Unit mesh: {} verts, {} indices, hash {:016x}",
+            render_mesh.vertices.len(),
+            render_mesh.index_count(),
+            render_mesh.content_hash(),
+        );
 
-        // Ground plane buffers — oversized beyond camera bounds (±50) so edges are never visible
-        let ground_mesh = build_ground_plane_mesh(100.0, 100.0);
-        let ground_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Ground Vertex Buffer"),
-            contents: ground_mesh.vertex_bytes(),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-        let ground_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Ground Index Buffer"),
-            contents: ground_mesh.index_bytes(),
-            usage: wgpu::BufferUsages::INDEX,
-        });
-        let ground_num_indices = ground_mesh.index_count() as u32;
-        let ground_instance = InstanceData {
-            position: [0.0, 0.0, 0.0],
-            _padding: 0.0,
-            color: [0.25, 0.45, 0.25, 1.0],  // dark green
+        // Drone mesh: same graph, one CC level, cage normals — a rounded cube.
+        let drone_mesh = build_drone_mesh(&unit_graph);
+
+        // Far spheres drop to one CC level; the limit surface keeps the silhouette size.
+        let sphere_lods = [render_mesh, build_procedural_sphere(&unit_graph, 1)];
+        let sphere_handle = renderer.register_mesh_lods("Sphere Unit", &sphere_lods, SPHERE_LOD_DISTANCE);
+        let unit_style = Material {
+            toon: true,
+            outline_width: UNIT_OUTLINE_WIDTH,
+            outline_color: UNIT_OUTLINE_COLOR,
+            ..Material::default()
         };
-        let ground_instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Ground Instance Buffer"),
-            contents: bytemuck::cast_slice(&[ground_instance]),
-            usage: wgpu::BufferUsages::VERTEX,
+        let unit_types = [
+            // Polished spheres: tight, bright highlight
+            (sphere_handle, renderer.register_material(Material { specular: 0.8, shininess: 64.0, ..unit_style })),
+            // Drones: satin finish
+            (renderer.register_mesh("Drone Unit", &drone_mesh), renderer.register_material(Material { specular: 0.3, shininess: 16.0, ..unit_style })),
+        ];
+
+        // Terrain chunks over the map, plus an apron out to twice its size so
+        // the map edge is never visible from inside the camera bounds.
+        use engine::navigation::WORLD_HALF;
+        let terrain = build_battlefield_terrain();
+        renderer.set_splat_map(&terrain.splat_map(TERRAIN_LAYER_COLORS));
+        renderer.set_terrain_height(terrain.max_height());
+        let terrain_chunks = terrain.chunk_meshes().iter().enumerate()
+            .map(|(i, chunk)| renderer.register_mesh(&format!("Terrain Chunk {i}"), chunk))
+            .collect();
+        let apron_mesh = renderer.register_mesh("Ground Apron", &build_ground_plane_mesh(2.0 * WORLD_HALF, 2.0 * WORLD_HALF));
+        let ground_material = renderer.register_material(Material { specular: 0.05, shininess: 8.0, splat: true, ..Material::default() });
+        let field_material = renderer.register_material(Material {
+            base_color: [1.0, 1.0, 1.0, 0.2],
+            emissive: 0.4,
+            transparent: true,
+            ..Material::default()
         });
 
-        let (depth_texture, depth_view) = Self::create_depth_texture(&device, &config);
-        let debug_overlay = DebugOverlay::new(&window, &device, config.format);
+        let debug_overlay = DebugOverlay::new(&window, renderer.device(), renderer.surface_format());
 
-        // Build navigation grid and compute flowfields for the crossing test.
-        let nav_grid = NavigationGrid::new_open(GRID_WIDTH, GRID_HEIGHT);
-        let groups = create_crossing_groups(&nav_grid);
+        // Build navigation grid (cliffs block movement, rough ground costs more) and compute paths for the crossing test.
+        let mut nav_grid = NavigationGrid::new_open(GRID_WIDTH, GRID_HEIGHT);
+        terrain.bake_navigation(&mut nav_grid);
+        let groups = create_crossing_groups(&nav_grid, &terrain, &unit_types);
 
         // Spatial grid for ORCA neighbour queries — 2-unit cells over the full map.
-        use engine::navigation::WORLD_HALF;
         let spatial_grid = SpatialGrid::new(
             glam::Vec2::new(-WORLD_HALF, -WORLD_HALF),
             glam::Vec2::new( WORLD_HALF,  WORLD_HALF),
@@ -570,36 +499,53 @@ impl State {
 
         // ECS world — 2000 units in 8 crossing groups.
         let mut world = World::new();
-        spawn_crossing_scene(&mut world, &groups);
+        let unit_sockets = AttachmentSockets::from_graph(&unit_graph);
+        let unit_pose = SkinPose::rest(&unit_graph);
+        let sparks = std::sync::Arc::new(core_spark_effect());
+        spawn_crossing_scene(&mut world, &groups, &terrain, &unit_sockets, &unit_pose, &sparks);
+        spawn_goal_beacons(&mut world, &groups, &std::sync::Arc::new(beacon_smoke_effect()));
+
+        // Permanent rally marks at each group's goal, in the group's color.
+        let mut decals = Decals::new();
+        for group in &groups {
+            let [r, g, b] = group.color;
+            decals.add(Decal::new(
+                DecalShape::Rally,
+                glam::Vec2::new(group.goal_world.x, group.goal_world.z),
+                glam::Vec2::splat(RALLY_RADIUS),
+                [r, g, b, RALLY_ALPHA],
+            ));
+        }
 
         Self {
-            surface,
-            device,
-            queue,
-            config,
-            size,
-            render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            instance_buffer,
-            num_indices,
-            max_instances,
-            ground_vertex_buffer,
-            ground_index_buffer,
-            ground_instance_buffer,
-            ground_num_indices,
-            uniform_buffer,
-            uniform_bind_group,
-            light_bind_group,
-            depth_texture,
-            depth_view,
+            renderer,
+            terrain,
+            terrain_chunks,
+            apron_mesh,
+            ground_material,
+            field_mesh: sphere_handle,
+            field_material,
             world,
             last_update: std::time::Instant::now(),
+            particles: ParticleSystem::new(),
+            decals,
+            placement_preview: None,
+            cursor_ground: None,
+            camera_bookmarks: [None; BOOKMARK_KEYS.len()],
+            follow_target: None,
+            events: EventLog::new(),
             formations: (0..groups.len()).map(|_| GroupFormation::new()).collect(),
             nav_grid,
             groups,
             spatial_grid,
-            camera: RtsCamera::new(),
+            camera: {
+                let mut camera = RtsCamera::new();
+                camera.zoomed_in_pitch = Some(CAMERA_ZOOMED_IN_PITCH_DEG.to_radians());
+                // Keep the orbit target over the navigable map.
+                camera.bounds_min = glam::Vec2::splat(-WORLD_HALF);
+                camera.bounds_max = glam::Vec2::splat(WORLD_HALF);
+                camera
+            },
             input: {
                 let mut input = InputState::new();
                 input.window_size = (size.width, size.height);
@@ -611,21 +557,14 @@ impl State {
             current_fps: 0,
             debug_overlay,
             debug_units_visible: false,
+            health_bar_visibility: HealthBarVisibility::OnDamage,
             pathfinding_last_ms: 0.0,
             flowfield_recompute_count: 0,
         }
     }
 
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
-        if new_size.width > 0 && new_size.height > 0 {
-            self.size = new_size;
-            self.config.width = new_size.width;
-            self.config.height = new_size.height;
-            self.surface.configure(&self.device, &self.config);
-            let (depth_texture, depth_view) = Self::create_depth_texture(&self.device, &self.config);
-            self.depth_texture = depth_texture;
-            self.depth_view = depth_view;
-        }
+        self.renderer.resize(new_size.width, new_size.height);
     }
 
     fn update(&mut self) {
@@ -639,8 +578,87 @@ impl State {
         if self.input.is_key_just_pressed(KeyCode::F4) {
             self.debug_units_visible = !self.debug_units_visible;
         }
+        if self.input.is_key_just_pressed(KeyCode::KeyH) {
+            self.health_bar_visibility = self.health_bar_visibility.next();
+        }
+        if self.input.is_key_just_pressed(KeyCode::KeyK) {
+            let mut query = self.world.query_filtered::<(&Transform, &mut Health), With<Selected>>();
+            let mut hit_sum = glam::Vec2::ZERO;
+            let mut hit_count = 0;
+            for (transform, mut health) in query.iter_mut(&mut self.world) {
+                health.damage(DEBUG_DAMAGE);
+                let p = glam::Vec2::new(transform.position.x, transform.position.z);
+                self.decals.add(
+                    Decal::new(DecalShape::Scorch, p, glam::Vec2::splat(SCORCH_RADIUS), SCORCH_COLOR)
+                        .with_lifetime(SCORCH_SECONDS, SCORCH_FADE),
+                );
+                hit_sum += p;
+                hit_count += 1;
+            }
+            if hit_count > 0 {
+                self.events.push(format!("{hit_count} units hit"), hit_sum / hit_count as f32);
+            }
+        }
+        if self.input.is_key_just_pressed(KeyCode::KeyB) {
+            self.placement_preview = match self.placement_preview.take() {
+                Some(id) => {
+                    self.decals.remove(id);
+                    None
+                }
+                None => {
+                    let footprint = Decal::new(DecalShape::Footprint, glam::Vec2::ZERO, glam::Vec2::ZERO, FOOTPRINT_VALID);
+                    Some(self.decals.add(footprint))
+                }
+            };
+        }
+        self.decals.update(dt);
+
+        // Camera bookmarks (Ctrl+F1/F2/F6–F9 store, F1/F2/F6–F9 recall), follow the
+        // selected unit (F) and jump through recent events (Space).
+        let ctrl = self.input.is_key_held(KeyCode::ControlLeft) || self.input.is_key_held(KeyCode::ControlRight);
+        for (slot, &key) in BOOKMARK_KEYS.iter().enumerate() {
+            if !self.input.is_key_just_pressed(key) {
+                continue;
+            }
+            if ctrl {
+                self.camera_bookmarks[slot] = Some(self.camera.view());
+            } else if let Some(view) = self.camera_bookmarks[slot] {
+                self.camera.fly_to(view);
+                self.follow_target = None;
+            }
+        }
+        if self.input.is_key_just_pressed(KeyCode::KeyF) {
+            self.follow_target = match self.follow_target {
+                Some(_) => None,
+                None => self.world.query_filtered::<Entity, With<Selected>>().iter(&self.world).next(),
+            };
+            if let Some(transform) = self.follow_target.and_then(|entity| self.world.get::<Transform>(entity)) {
+                let target = glam::Vec2::new(transform.position.x, transform.position.z);
+                self.camera.fly_to(CameraView { target, ..self.camera.view() });
+            }
+        }
+        if self.input.is_key_just_pressed(KeyCode::Space) && let Some(event) = self.events.cycle() {
+            self.camera.fly_to(CameraView { target: event.position, ..self.camera.view() });
+            self.follow_target = None;
+        }
 
         self.camera.update(&self.input, dt);
+        if let Some(entity) = self.follow_target {
+            match self.world.get::<Transform>(entity) {
+                Some(transform) => self.camera.follow(glam::Vec2::new(transform.position.x, transform.position.z)),
+                None => self.follow_target = None,
+            }
+        }
+        let target = self.camera.target();
+        self.camera.follow_ground(self.terrain.height_at(target.x, target.y), dt);
+
+        // Ground under the cursor, for the placement preview and unit picking.
+        let ray = self.camera.screen_ray(glam::Vec2::from(self.input.mouse_position), self.renderer.size());
+        self.cursor_ground = self.terrain.raycast(&ray, self.camera.far).map(|t| ray.at(t));
+        if let Some(id) = self.placement_preview {
+            let site = self.cursor_ground.map_or(target, |p| glam::Vec2::new(p.x, p.z));
+            self.update_placement_preview(id, site);
+        }
 
         // ── Pathfinding + ORCA local avoidance ─────────────────────────────
         //
@@ -753,6 +771,8 @@ impl State {
                     "Group {} arrived ({}/{} units at goal).",
                     gid, n_arrived, total,
                 );
+                let goal = self.groups[gid].goal_world;
+                self.events.push(format!("Group {gid} arrived"), glam::Vec2::new(goal.x, goal.z));
                 continue;
             }
 
@@ -951,174 +971,259 @@ impl State {
         // ── 6. Integrate positions ───────────────────────────────────────────
         {
             let mut query = self.world.query::<(&mut Transform, &Velocity)>();
+            let turn = (UNIT_TURN_RATE * dt).min(1.0);
             for (mut transform, velocity) in query.iter_mut(&mut self.world) {
                 transform.position += velocity.linear * dt;
-                transform.position.y = 0.5; // keep spheres on the ground plane
+                // Keep spheres resting on the terrain surface.
+                transform.position.y = self.terrain.height_at(transform.position.x, transform.position.z) + UNIT_RADIUS;
+                // Ease toward facing the direction of travel; keep heading when stopped.
+                if velocity.linear.length_squared() > 0.01
+                    && let Some(facing) = Transform::facing_rotation(velocity.linear)
+                {
+                    transform.rotation = transform.rotation.slerp(facing, turn);
+                }
+            }
+        }
+
+        // ── 7. Particles (spawn at post-move emitter positions) ─────────────
+        self.particles.update(&mut self.world, dt);
+
+        // ── 8. Health: damage timers and shield recharge ────────────────────
+        for mut health in self.world.query::<&mut Health>().iter_mut(&mut self.world) {
+            health.since_damage += dt;
+            if health.since_damage > SHIELD_REGEN_DELAY {
+                health.shield = (health.shield + SHIELD_REGEN_RATE * dt).min(health.max_shield);
+            }
+        }
+
+        // ── 9. Hover + click selection (against post-move positions) ────────
+        self.update_selection();
+
+    }
+
+    /// Mark the unit under the cursor `Hovered`; a left click selects it
+    /// (Shift adds to the selection) or, over empty ground, clears the selection.
+    fn update_selection(&mut self) {
+        // The unit whose silhouette is under the cursor, from the GPU pick buffer
+        // (a frame or two behind the cursor). Until the first readback lands,
+        // fall back to the first bounding sphere along the cursor ray.
+        let (mouse_x, mouse_y) = self.input.mouse_position;
+        self.renderer.request_pick((mouse_x.max(0.0) as u32, mouse_y.max(0.0) as u32));
+        let hovered = match self.renderer.last_pick() {
+            Some(pick) => pick.id
+                .and_then(|id| Entity::try_from_bits(id).ok())
+                .filter(|&entity| self.world.get::<UnitAgent>(entity).is_some()),
+            None => {
+                let ray = self.camera.screen_ray(glam::Vec2::new(mouse_x, mouse_y), self.renderer.size());
+                let ground = self.cursor_ground.map_or(f32::INFINITY, |p| p.distance(ray.origin));
+                let mut query = self.world.query_filtered::<(Entity, &Transform, &UnitAgent), With<Health>>();
+                let candidates = query.iter(&self.world).map(|(entity, transform, agent)| (entity, transform.position, agent.radius));
+                pick_nearest(&ray, candidates, ground).map(|(entity, _)| entity)
+            }
+        };
+
+        let previous: Vec<Entity> = self.world.query_filtered::<Entity, With<Hovered>>().iter(&self.world).collect();
+        for entity in previous {
+            self.world.entity_mut(entity).remove::<Hovered>();
+        }
+        if let Some(entity) = hovered {
+            self.world.entity_mut(entity).insert(Hovered);
+        }
+
+        if self.input.left_mouse_clicked {
+            let additive = self.input.is_key_held(KeyCode::ShiftLeft) || self.input.is_key_held(KeyCode::ShiftRight);
+            if !additive {
+                let selected: Vec<Entity> = self.world.query_filtered::<Entity, With<Selected>>().iter(&self.world).collect();
+                for entity in selected {
+                    self.world.entity_mut(entity).remove::<Selected>();
+                }
+            }
+            if let Some(entity) = hovered {
+                self.world.entity_mut(entity).insert(Selected);
             }
         }
+    }
+
+    /// Snap the footprint preview to the tile grid under `site` and tint it
+    /// by whether every tile it covers is walkable and level.
+    fn update_placement_preview(&mut self, id: DecalId, site: glam::Vec2) {
+        let half = FOOTPRINT_TILES as f32 * 0.5;
+        let min_cell = self.nav_grid.world_to_cell_clamped(Vec3::new(site.x - half, 0.0, site.y - half));
+        let min_cell = min_cell.min(glam::UVec2::new(GRID_WIDTH, GRID_HEIGHT) - FOOTPRINT_TILES);
+        let cells: Vec<glam::UVec2> = (0..FOOTPRINT_TILES)
+            .flat_map(|z| (0..FOOTPRINT_TILES).map(move |x| min_cell + glam::UVec2::new(x, z)))
+            .collect();
+        let base = self.nav_grid.cell_center(min_cell);
+        let level = self.terrain.height_at(base.x, base.z);
+        let buildable = cells.iter().all(|&cell| {
+            let c = self.nav_grid.cell_center(cell);
+            self.nav_grid.is_walkable(cell) && (self.terrain.height_at(c.x, c.z) - level).abs() < 0.01
+        });
 
+        let Some(footprint) = self.decals.get_mut(id) else { return };
+        footprint.center = glam::Vec2::new(base.x, base.z) + glam::Vec2::splat(half - 0.5);
+        footprint.half_size = glam::Vec2::splat(half);
+        footprint.color = if buildable { FOOTPRINT_VALID } else { FOOTPRINT_BLOCKED };
     }
 
     fn render(&mut self, window: &winit::window::Window) -> Result<(), wgpu::SurfaceError> {
-        let output = self.surface.get_current_texture()?;
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Collect instance data from ECS
-        let mut instance_data = Vec::new();
-        let mut query = self.world.query::<(&Transform, &EntityColor)>();
-        for (transform, color) in query.iter(&self.world) {
-            instance_data.push(InstanceData {
-                position: transform.position.to_array(),
-                _padding: 0.0,
-                color: [color.r, color.g, color.b, 1.0],
-            });
+        // Terrain chunks (culled individually) and the apron just below them
+        const GROUND_COLOR: [f32; 4] = [0.25, 0.45, 0.25, 1.0]; // dark green
+        for &chunk in &self.terrain_chunks {
+            self.renderer.submit(chunk, self.ground_material, Mat4::IDENTITY, GROUND_COLOR, 0.0);
+        }
+        let apron = Mat4::from_translation(Vec3::new(0.0, -0.01, 0.0));
+        self.renderer.submit(self.apron_mesh, self.ground_material, apron, GROUND_COLOR, 0.0);
+
+        // Units: one instance per entity; the renderer batches them per mesh
+        let mut query = self.world.query::<(Entity, &Transform, &EntityColor, &MeshHandle, &MaterialHandle, Option<&Emissive>)>();
+        for (entity, transform, color, mesh, material, emissive) in query.iter(&self.world) {
+            let emissive = emissive.map_or(0.0, |e| e.strength);
+            let color = [color.r, color.g, color.b, 1.0];
+            self.renderer.submit_pickable(*mesh, *material, transform.matrix(), color, emissive, pick_id(entity));
         }
 
-        let instance_count = instance_data.len().min(self.max_instances);
-        if !instance_data.is_empty() {
-            self.queue.write_buffer(
-                &self.instance_buffer,
-                0,
-                bytemuck::cast_slice(&instance_data[..instance_count]),
+        // Energy-field domes over each group's goal (transparent pass)
+        for group in &self.groups {
+            let model = Mat4::from_scale_rotation_translation(
+                Vec3::splat(8.0), glam::Quat::IDENTITY, group.goal_world,
             );
+            let [r, g, b] = group.color;
+            self.renderer.submit(self.field_mesh, self.field_material, model, [r, g, b, 1.0], 0.0);
         }
 
-        // Update camera uniforms
-        let aspect = self.size.width as f32 / self.size.height as f32;
-        let uniforms = Uniforms {
-            view_proj: self.camera.view_projection(aspect).to_cols_array_2d(),
-            camera_pos: self.camera.camera_position().to_array(),
-            _padding: 0,
-        };
-        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        // Point lights carried by entities (the renderer keeps the nearest MAX_POINT_LIGHTS)
+        let mut query = self.world.query::<(&Transform, &PointLightEmitter)>();
+        for (transform, emitter) in query.iter(&self.world) {
+            let position = transform.matrix().transform_point3(emitter.offset);
+            self.renderer.submit_point_light(PointLight::new(position, emitter.color, emitter.radius));
+        }
 
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Render Encoder"),
-        });
+        // Ground decals (rally marks, scorch marks, placement preview)
+        self.decals.submit(&mut self.renderer, &self.terrain);
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.05, g: 0.05, b: 0.1, a: 1.0 }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &self.depth_view,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
-                }),
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
+        // Particle billboards (sparks, smoke)
+        self.particles.submit(&mut self.renderer);
 
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-            render_pass.set_bind_group(1, &self.light_bind_group, &[]);
-            // Draw ground plane (1 instance at origin)
-            render_pass.set_vertex_buffer(0, self.ground_vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, self.ground_instance_buffer.slice(..));
-            render_pass.set_index_buffer(self.ground_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(0..self.ground_num_indices, 0, 0..1);
-
-            // Draw spheres (instanced)
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..instance_count as u32);
-        }
+        // Selection rings and health bars
+        submit_world_ui(&mut self.world, &mut self.renderer, self.health_bar_visibility);
+
+        self.renderer.set_camera(
+            self.camera.view_projection(self.renderer.aspect()),
+            self.camera.camera_position(),
+        );
 
         // Debug overlay (egui) — F3 = stats, F4 = unit circles, F5 = flowfield/density.
         // Run one egui frame covering all active layers so we tessellate only once.
-        if self.debug_overlay.visible || self.debug_units_visible {
-            let ppp = window.scale_factor() as f32;
-            let sw  = self.config.width  as f32;
-            let sh  = self.config.height as f32;
-
-            // ── F3 stats ────────────────────────────────────────────────────
-            let stats: Option<DebugStats> = if self.debug_overlay.visible {
-                let entity_count = self.world.query::<&Transform>().iter(&self.world).count();
-                let (avg, mn, mx) = if !self.frame_times.is_empty() {
-                    let avg = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
-                    let mn  = self.frame_times.iter().copied().fold(f32::INFINITY, f32::min);
-                    let mx  = self.frame_times.iter().copied().fold(0.0_f32, f32::max);
-                    (avg, mn, mx)
-                } else { (0.0, 0.0, 0.0) };
-
-                Some(DebugStats {
-                    fps: self.current_fps,
-                    frame_time_avg_ms: avg * 1000.0,
-                    frame_time_min_ms: mn  * 1000.0,
-                    frame_time_max_ms: mx  * 1000.0,
-                    entity_count,
-                    draw_calls: 1,
-                    resolution: (self.config.width, self.config.height),
-                    camera_target: (self.camera.target().x, self.camera.target().y),
-                    camera_distance: self.camera.distance(),
-                    camera_zoom_pct: self.camera.zoom_fraction() * 100.0,
-                    pathfinding_ms: self.pathfinding_last_ms,
-                    flowfield_recomputes: self.flowfield_recompute_count,
-                })
-            } else { None };
-
-            // ── F4 unit debug ────────────────────────────────────────────────
-            let unit_draws: Option<Vec<UnitDebugDraw>> = if self.debug_units_visible {
-                let aspect = sw / sh;
-                let vp = self.camera.view_projection(aspect);
-
-                let draws = {
-                    let mut q = self.world.query::<(&Transform, &Velocity, &UnitAgent)>();
-                    q.iter(&self.world).filter_map(|(tf, vel, agent)| {
-                        let p = tf.position;
-
-                        let center = world_to_screen(p, vp, sw, sh, ppp)?;
-
-                        // Velocity arrow: project 0.5 s of travel ahead.
-                        let tip_world = p + vel.linear * 0.5;
-                        let vel_tip = world_to_screen(tip_world, vp, sw, sh, ppp)
-                            .unwrap_or(center);
-
-                        // Avoidance radius: project a point one radius to the right.
-                        let edge_world = Vec3::new(p.x + agent.radius, p.y, p.z);
-                        let radius_px = world_to_screen(edge_world, vp, sw, sh, ppp)
-                            .map(|ep| ((ep.x - center.x).powi(2) + (ep.y - center.y).powi(2)).sqrt())
-                            .unwrap_or(5.0);
-
-                        Some(UnitDebugDraw { pos: center, vel_tip, radius_px })
-                    }).collect()
-                };
-                Some(draws)
-            } else { None };
-
-            let screen_descriptor = egui_wgpu::ScreenDescriptor {
-                size_in_pixels: [self.config.width, self.config.height],
-                pixels_per_point: ppp,
+        let overlay_visible = self.debug_overlay.visible || self.debug_units_visible;
+        let (width, height) = self.renderer.size();
+        let ppp = window.scale_factor() as f32;
+        let sw  = width  as f32;
+        let sh  = height as f32;
+
+        // ── F3 stats ────────────────────────────────────────────────────
+        let stats: Option<DebugStats> = if self.debug_overlay.visible {
+            let entity_count = self.world.query::<&Transform>().iter(&self.world).count();
+            let (avg, mn, mx) = if !self.frame_times.is_empty() {
+                let avg = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
+                let mn  = self.frame_times.iter().copied().fold(f32::INFINITY, f32::min);
+                let mx  = self.frame_times.iter().copied().fold(0.0_f32, f32::max);
+                (avg, mn, mx)
+            } else { (0.0, 0.0, 0.0) };
+            let (instance_buffer_bytes, instance_buffer_reallocs) = self.renderer.instance_buffer_stats();
+            let (point_lights_shaded, point_lights_submitted) = self.renderer.point_light_stats();
+            let cull = self.renderer.cull_stats();
+
+            Some(DebugStats {
+                fps: self.current_fps,
+                frame_time_avg_ms: avg * 1000.0,
+                frame_time_min_ms: mn  * 1000.0,
+                frame_time_max_ms: mx  * 1000.0,
+                entity_count,
+                draw_calls: self.renderer.draw_calls(),
+                instances_visible: cull.visible,
+                instances_shadow_only: cull.shadow_only,
+                instances_culled: cull.culled,
+                instance_buffer_bytes,
+                instance_buffer_reallocs,
+                point_lights_shaded,
+                point_lights_submitted,
+                particles_alive: self.particles.particle_count(),
+                particles_drawn: self.renderer.particles_drawn(),
+                decals: self.decals.len(),
+                placement_preview: self.placement_preview.is_some(),
+                resolution: (width, height),
+                camera_target: (self.camera.target().x, self.camera.target().y),
+                cursor_ground: self.cursor_ground.map(|p| (p.x, p.z)),
+                camera_distance: self.camera.distance(),
+                camera_zoom_pct: self.camera.zoom_fraction() * 100.0,
+                camera_pitch_deg: self.camera.current_pitch().to_degrees(),
+                camera_following: self.follow_target.is_some(),
+                camera_bookmarks: self.camera_bookmarks.iter().filter(|b| b.is_some()).count(),
+                events: self.events.len(),
+                last_event: self.events.latest().map(|e| e.message.clone()),
+                pathfinding_ms: self.pathfinding_last_ms,
+                flowfield_recomputes: self.flowfield_recompute_count,
+                health_bars: self.health_bar_visibility.label(),
+            })
+        } else { None };
+
+        // ── F4 unit debug ────────────────────────────────────────────────
+        let unit_draws: Option<Vec<UnitDebugDraw>> = if self.debug_units_visible {
+            let aspect = sw / sh;
+            let vp = self.camera.view_projection(aspect);
+
+            let draws = {
+                let mut q = self.world.query::<(Entity, &Transform, &Velocity, &UnitAgent)>();
+                q.iter(&self.world).filter_map(|(entity, tf, vel, agent)| {
+                    let p = tf.position;
+
+                    let center = world_to_screen(p, vp, sw, sh, ppp)?;
+
+                    // Velocity arrow: project 0.5 s of travel ahead.
+                    let tip_world = p + vel.linear * 0.5;
+                    let vel_tip = world_to_screen(tip_world, vp, sw, sh, ppp)
+                        .unwrap_or(center);
+
+                    // Avoidance radius: project a point one radius to the right.
+                    let edge_world = Vec3::new(p.x + agent.radius, p.y, p.z);
+                    let radius_px = world_to_screen(edge_world, vp, sw, sh, ppp)
+                        .map(|ep| ((ep.x - center.x).powi(2) + (ep.y - center.y).powi(2)).sqrt())
+                        .unwrap_or(5.0);
+
+                    // Attachment sockets: small markers at each socket's world position.
+                    let sockets = socket_world_transforms(&self.world, entity).iter()
+                        .filter_map(|(_, m)| world_to_screen(m.w_axis.truncate(), vp, sw, sh, ppp))
+                        .collect();
+
+                    Some(UnitDebugDraw { pos: center, vel_tip, radius_px, sockets })
+                }).collect()
             };
+            Some(draws)
+        } else { None };
 
-            self.debug_overlay.render(
-                &self.device,
-                &self.queue,
-                &mut encoder,
-                window,
-                &view,
-                &screen_descriptor,
-                stats.as_ref(),
-                unit_draws.as_deref(),
-                None,
-                None,
-            );
-        }
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [width, height],
+            pixels_per_point: ppp,
+        };
 
-        self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
-        Ok(())
+        // Quality settings are edited in the F3 panel and applied after the frame.
+        let mut settings = self.renderer.settings();
+        let settings_visible = self.debug_overlay.visible;
+        let debug_overlay = &mut self.debug_overlay;
+        let result = self.renderer.render(|frame| {
+            if !overlay_visible { return; }
+            debug_overlay.render(frame, window, &screen_descriptor, DebugLayers {
+                stats: stats.as_ref(),
+                settings: settings_visible.then_some(&mut settings),
+                unit_draws: unit_draws.as_deref(),
+                ..DebugLayers::default()
+            });
+        });
+        if settings != self.renderer.settings() {
+            self.renderer.set_settings(settings);
+        }
+        result
     }
 }
 
@@ -1134,13 +1239,18 @@ impl State {
 /// Each unit receives a `FormationOffset` equal to its spawn position minus the
 /// group centroid (`start_world.xz`). The formation system uses this fixed
 /// offset every frame so the group keeps its original grid shape throughout.
-fn spawn_crossing_scene(world: &mut World, groups: &[UnitGroup]) {
+fn spawn_crossing_scene(world: &mut World, groups: &[UnitGroup], terrain: &Terrain, sockets: &AttachmentSockets, pose: &SkinPose, sparks: &std::sync::Arc<ParticleEffect>) {
     // FORM_WIDE and FORM_SPACING are module-level constants shared with the formation system.
     const FORM_DEEP: u32 = 5; // rows along travel direction
 
     let half_w = (FORM_WIDE  as f32 - 1.0) * FORM_SPACING * 0.5;
     let half_d = (FORM_DEEP  as f32 - 1.0) * FORM_SPACING * 0.5;
 
+    // Energy cores hover at the "overhead" socket.
+    let core_offset = sockets.resolve(pose)
+        .find(|s| s.name == "overhead")
+        .map_or(Vec3::Y * 0.5, |s| s.position);
+
     let mut total = 0u32;
     for group in groups {
         // Unit travel direction in the XZ plane (normalised).
@@ -1170,8 +1280,9 @@ fn spawn_crossing_scene(world: &mut World, groups: &[UnitGroup]) {
                     + perp.y   * offset_perp
                     + travel.y * offset_travel;
 
-                let spawn_x = world_x.clamp(-49.0, 49.0);
-                let spawn_z = world_z.clamp(-49.0, 49.0);
+                let limit = engine::navigation::WORLD_HALF - 1.0;
+                let spawn_x = world_x.clamp(-limit, limit);
+                let spawn_z = world_z.clamp(-limit, limit);
 
                 // Fixed offset from group centroid — preserved for the whole journey.
                 let formation_offset = glam::Vec2::new(
@@ -1183,14 +1294,30 @@ fn spawn_crossing_scene(world: &mut World, groups: &[UnitGroup]) {
                 // first holds course (30% ORCA responsibility); groups issued later
                 // yield and part to make way (70% responsibility).
                 let priority = group.command_order;
-                world.spawn((
-                    Transform::from_position(Vec3::new(spawn_x, 0.5, spawn_z)),
+                let mut unit = world.spawn((
+                    Transform {
+                        rotation: Transform::facing_rotation(to_goal).unwrap_or_default(),
+                        scale: group.scale,
+                        ..Transform::from_position(Vec3::new(spawn_x, terrain.height_at(spawn_x, spawn_z) + UNIT_RADIUS, spawn_z))
+                    },
                     Velocity { linear: Vec3::ZERO },
                     EntityColor { r: group.color[0], g: group.color[1], b: group.color[2] },
                     GroupMembership { group_id: group.id },
                     UnitAgent { radius: UNIT_RADIUS, max_speed: UNIT_SPEED, priority },
                     FormationOffset { offset: formation_offset },
+                    group.mesh,
+                    group.material,
+                    sockets.clone(),
+                    pose.clone(),
+                    Health::new(UNIT_MAX_HP, if group.energy_core { CORE_MAX_SHIELD } else { 0.0 }),
                 ));
+                if group.energy_core {
+                    unit.insert((
+                        Emissive { strength: CORE_EMISSIVE },
+                        PointLightEmitter { color: group.color, radius: CORE_LIGHT_RADIUS, offset: core_offset },
+                        ParticleEmitter::new(sparks.clone(), core_offset, PARTICLE_SEED + total as u64),
+                    ));
+                }
                 total += 1;
             }
         }
@@ -1198,6 +1325,17 @@ fn spawn_crossing_scene(world: &mut World, groups: &[UnitGroup]) {
     println!("Crossing scene: {} units across {} groups", total, groups.len());
 }
 
+/// Smoke columns marking each group's goal: emitter-only entities.
+fn spawn_goal_beacons(world: &mut World, groups: &[UnitGroup], smoke: &std::sync::Arc<ParticleEffect>) {
+    for group in groups {
+        world.spawn((
+            Transform::from_position(group.goal_world),
+            // Seeds above any unit's so beacons never share a stream with sparks.
+            ParticleEmitter::new(smoke.clone(), Vec3::ZERO, PARTICLE_SEED + u32::MAX as u64 + group.id as u64),
+        ));
+    }
+}
+
 // ============================================================================
 // MAIN
 // ============================================================================
@@ -1233,8 +1371,13 @@ impl ApplicationHandler for App {
         let Some(window) = &self.window else { return };
         let Some(state) = &mut self.state else { return };
 
-        let _ = state.debug_overlay.handle_window_event(window, &event);
-        state.input.process_event(&event);
+        let overlay = state.debug_overlay.handle_window_event(window, &event);
+        // Clicks on overlay widgets must not also select units.
+        let overlay_click = overlay.consumed
+            && matches!(event, WindowEvent::MouseInput { button: winit::event::MouseButton::Left, .. });
+        if !overlay_click {
+            state.input.process_event(&event);
+        }
 
         match event {
             WindowEvent::CloseRequested
@@ -1257,7 +1400,7 @@ impl ApplicationHandler for App {
                 state.update();
                 match state.render(window) {
                     Ok(_) => {}
-                    Err(wgpu::SurfaceError::Lost) => state.resize(state.size),
+                    Err(wgpu::SurfaceError::Lost) => state.resize(window.inner_size()),
                     Err(wgpu::SurfaceError::OutOfMemory) => event_loop.exit(),
                     Err(e) => eprintln!("{:?}", e),
                 }
@@ -1302,6 +1445,13 @@ impl ApplicationHandler for App {
     }
 }
 
+/// Pick-buffer ID of a unit entity: its full bits, generation included, so a
+/// late readback never resolves to a despawned unit's reused slot. Never 0,
+/// which is reserved for "nothing".
+fn pick_id(entity: Entity) -> u64 {
+    entity.to_bits()
+}
+
 /// Project a world-space position to egui screen points.
 ///
 /// Returns `None` if the point is behind the camera or far off-screen.
@@ -1323,9 +1473,102 @@ fn world_to_screen(
     Some(egui::pos2(px, py))
 }
 
+/// Fixed reference scene for `--screenshot` and the image-diff tests: the
+/// procedural sphere with toon-outlined, polished, emissive (plus point light)
+/// and transparent materials on a matte ground patch, plus alpha and additive particles
+/// and world-space UI, from a fixed camera.
+fn load_sphere_scene(renderer: &mut Renderer) {
+    renderer.set_light(LightUniform::new());
+
+    let graph = build_unit_skin_graph();
+    let sphere_lods = [build_procedural_sphere(&graph, 2), build_procedural_sphere(&graph, 1)];
+    let sphere = renderer.register_mesh_lods("Sphere", &sphere_lods, SPHERE_LOD_DISTANCE);
+    let ground = renderer.register_mesh("Ground", &build_ground_plane_mesh(8.0, 8.0));
+    let matte = renderer.register_material(Material { specular: 0.05, shininess: 8.0, ..Material::default() });
+    let polished = renderer.register_material(Material { specular: 0.8, shininess: 64.0, ..Material::default() });
+    let toon = renderer.register_material(Material {
+        specular: 0.3,
+        shininess: 16.0,
+        toon: true,
+        outline_width: UNIT_OUTLINE_WIDTH,
+        outline_color: UNIT_OUTLINE_COLOR,
+        ..Material::default()
+    });
+    let glass = renderer.register_material(Material {
+        base_color: [1.0, 1.0, 1.0, 0.25],
+        transparent: true,
+        ..Material::default()
+    });
+
+    let at = |x: f32, z: f32| Mat4::from_translation(Vec3::new(x, 0.5, z));
+    renderer.submit(ground, matte, Mat4::IDENTITY, [0.25, 0.45, 0.25, 1.0], 0.0);
+    renderer.submit(sphere, toon, at(-1.5, 0.0), [0.8, 0.2, 0.2, 1.0], 0.0);
+    renderer.submit(sphere, polished, at(0.0, -1.0), [0.2, 0.4, 0.9, 1.0], 0.0);
+    renderer.submit(sphere, polished, at(1.5, 0.0), [0.9, 0.8, 0.3, 1.0], 1.2);
+    renderer.submit_point_light(PointLight::new(Vec3::new(1.5, 1.2, 0.8), [1.0, 0.7, 0.3], CORE_LIGHT_RADIUS));
+    renderer.submit(sphere, glass, at(0.0, 1.0) * Mat4::from_scale(Vec3::splat(1.8)), [0.6, 0.9, 1.0, 1.0], 0.0);
+
+    // Smoke puffs behind the matte sphere and sparks over the emissive one.
+    for (i, x) in [-2.2f32, -1.6, -1.0].into_iter().enumerate() {
+        renderer.submit_particle(ParticleBlend::Alpha, ParticleInstance {
+            position: [x, 1.2 + 0.4 * i as f32, -1.2],
+            size: 0.9 + 0.3 * i as f32,
+            color: [0.4, 0.4, 0.42, 0.6],
+        });
+    }
+    for (x, y) in [(1.3f32, 1.3f32), (1.6, 1.5), (1.8, 1.2)] {
+        renderer.submit_particle(ParticleBlend::Additive, ParticleInstance {
+            position: [x, y, 0.2],
+            size: 0.25,
+            color: [3.0, 2.0, 0.8, 1.0],
+        });
+    }
+
+    // Selection ring under the matte sphere; health bars over it and the polished one.
+    renderer.submit_selection_ring(Vec3::new(-1.5, 0.02, 0.0), 0.65, [0.3, 1.0, 0.4, 0.9]);
+    renderer.submit_status_bar(Vec3::new(-1.5, 1.35, 0.0), 1.0, 0.7, None, [0.6, 1.0, 0.1, 1.0]);
+    renderer.submit_status_bar(Vec3::new(0.0, 1.35, -1.0), 1.0, 0.3, Some(0.5), [1.0, 0.6, 0.1, 1.0]);
+
+    // Ground decals draped over a flat terrain matching the ground plane.
+    let flat = Terrain::new(8, 8, glam::Vec2::splat(-4.0), 1.0);
+    let mut decals = Decals::new();
+    decals.add(Decal::new(DecalShape::Footprint, glam::Vec2::new(-2.4, 1.8), glam::Vec2::splat(1.0), FOOTPRINT_VALID));
+    decals.add(Decal::new(DecalShape::Rally, glam::Vec2::new(2.4, 1.8), glam::Vec2::splat(0.8), [0.3, 0.6, 1.0, RALLY_ALPHA]));
+    decals.add(Decal::new(DecalShape::Scorch, glam::Vec2::new(-0.2, 2.6), glam::Vec2::splat(SCORCH_RADIUS), SCORCH_COLOR));
+    decals.submit(renderer, &flat);
+
+    let (width, height) = renderer.size();
+    let eye = Vec3::new(0.0, 4.0, 6.0);
+    let view = Mat4::look_at_rh(eye, Vec3::new(0.0, 0.5, 0.0), Vec3::Y);
+    let proj = Mat4::perspective_rh(45f32.to_radians(), width as f32 / height as f32, 0.1, 100.0);
+    renderer.set_camera(proj * view, eye);
+}
+
+/// `--screenshot <path>`: render the sphere scene headless and write it as PNG.
+fn screenshot(path: &str) {
+    let (width, height) = SCREENSHOT_SIZE;
+    let Some(mut renderer) = pollster::block_on(Renderer::new_headless(width, height)) else {
+        eprintln!("No software adapter available for headless rendering");
+        std::process::exit(1);
+    };
+    load_sphere_scene(&mut renderer);
+    let image: Image = renderer.render_to_image();
+    if let Err(e) = image.write_png(path) {
+        eprintln!("Failed to write {path}: {e}");
+        std::process::exit(1);
+    }
+    println!("Wrote {path} ({width}x{height})");
+}
+
 fn main() {
     env_logger::init();
 
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, flag, path] = args.as_slice() && flag == "--screenshot" {
+        screenshot(path);
+        return;
+    }
+
     let event_loop = EventLoop::new().unwrap();
     event_loop.set_control_flow(ControlFlow::Poll);
 
@@ -1336,3 +1579,23 @@ fn main() {
 
     event_loop.run_app(&mut app).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_ray_inverts_world_to_screen() {
+        let (width, height) = (1280, 720);
+        let camera = RtsCamera::new();
+        let vp = camera.view_projection(width as f32 / height as f32);
+        for world in [Vec3::ZERO, Vec3::new(3.0, 0.0, -2.0), Vec3::new(-4.0, 1.5, 1.0)] {
+            let pixel = world_to_screen(world, vp, width as f32, height as f32, 1.0).expect("point on screen");
+            let ray = camera.screen_ray(glam::Vec2::new(pixel.x, pixel.y), (width, height));
+            // The ray through the projected pixel passes back through the point.
+            let along = (world - ray.origin).dot(ray.direction);
+            assert!(along > 0.0);
+            assert!(ray.at(along).distance(world) < 1e-3, "{world}: ray misses by {}", ray.at(along).distance(world));
+        }
+    }
+}
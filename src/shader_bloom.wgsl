@@ -0,0 +1,71 @@
+// Bloom: threshold + downsample chain, then tent-filtered upsample back up.
+// Each pass draws one fullscreen triangle into one mip of the bloom texture.
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // Triangle covering the screen: (-1,-1), (3,-1), (-1,3)
+    let xy = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(xy * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(xy.x, 1.0 - xy.y);
+    return out;
+}
+
+struct BloomParams {
+    // 1 / source texture size
+    source_texel: vec2<f32>,
+    threshold: f32,
+    // Soft-knee width below the threshold
+    knee: f32,
+    // 1 on the first downsample (reads the HDR scene), 0 otherwise
+    prefilter: u32,
+}
+
+@group(0) @binding(0)
+var bloom_source: texture_2d<f32>;
+@group(0) @binding(1)
+var bloom_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> bloom: BloomParams;
+
+// Keep only the part of `color` above the threshold, with a quadratic knee.
+fn threshold(color: vec3<f32>) -> vec3<f32> {
+    let brightness = max(color.r, max(color.g, color.b));
+    var soft = clamp(brightness - bloom.threshold + bloom.knee, 0.0, 2.0 * bloom.knee);
+    soft = soft * soft / (4.0 * bloom.knee + 1e-4);
+    let contribution = max(soft, brightness - bloom.threshold) / max(brightness, 1e-4);
+    return color * contribution;
+}
+
+@fragment
+fn fs_downsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Four bilinear taps one texel out: a 4×4 box over the source.
+    let t = bloom.source_texel;
+    var color = textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(-t.x, -t.y)).rgb;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>( t.x, -t.y)).rgb;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(-t.x,  t.y)).rgb;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>( t.x,  t.y)).rgb;
+    color *= 0.25;
+    return vec4<f32>(select(color, threshold(color), bloom.prefilter == 1u), 1.0);
+}
+
+@fragment
+fn fs_upsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    // 3×3 tent filter over the smaller mip; blended additively into the larger one.
+    let t = bloom.source_texel;
+    var color = textureSample(bloom_source, bloom_sampler, in.uv).rgb * 4.0;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(-t.x, 0.0)).rgb * 2.0;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>( t.x, 0.0)).rgb * 2.0;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(0.0, -t.y)).rgb * 2.0;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(0.0,  t.y)).rgb * 2.0;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(-t.x, -t.y)).rgb;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>( t.x, -t.y)).rgb;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>(-t.x,  t.y)).rgb;
+    color += textureSample(bloom_source, bloom_sampler, in.uv + vec2<f32>( t.x,  t.y)).rgb;
+    return vec4<f32>(color / 16.0, 1.0);
+}
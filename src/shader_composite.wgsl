@@ -0,0 +1,69 @@
+// Final composite into the surface: bloom add → exposure + ACES tone map →
+// colour grade. Draws one fullscreen triangle.
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // Triangle covering the screen: (-1,-1), (3,-1), (-1,3)
+    let xy = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(xy * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(xy.x, 1.0 - xy.y);
+    return out;
+}
+
+struct CompositeParams {
+    bloom_intensity: f32,
+    exposure: f32,
+    contrast: f32,
+    saturation: f32,
+    // Multiplied into the graded colour (warm/cool shift)
+    tint: vec3<f32>,
+    // Bit 0 = bloom, bit 1 = tone map, bit 2 = colour grade
+    flags: u32,
+}
+
+@group(0) @binding(0)
+var scene_hdr: texture_2d<f32>;
+@group(0) @binding(1)
+var bloom_result: texture_2d<f32>;
+@group(0) @binding(2)
+var composite_sampler: sampler;
+@group(0) @binding(3)
+var<uniform> composite: CompositeParams;
+
+// ACES filmic curve fit (Narkowicz 2015)
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(scene_hdr, composite_sampler, in.uv).rgb;
+    let bloom_color = textureSample(bloom_result, composite_sampler, in.uv).rgb;
+
+    if ((composite.flags & 1u) != 0u) {
+        color += bloom_color * composite.bloom_intensity;
+    }
+    if ((composite.flags & 2u) != 0u) {
+        color = aces(color * composite.exposure);
+    }
+    if ((composite.flags & 4u) != 0u) {
+        let luma = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+        color = mix(vec3<f32>(luma), color, composite.saturation);
+        color = (color - 0.18) * composite.contrast + 0.18;
+        color = max(color * composite.tint, vec3<f32>(0.0));
+    }
+
+    // Surface is sRGB: output stays linear, the hardware encodes.
+    return vec4<f32>(color, 1.0);
+}
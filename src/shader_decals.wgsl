@@ -0,0 +1,116 @@
+// Ground decals: footprints, rally marks and scorch marks draped over the
+// terrain. Vertices carry the decal-local offset (world units from the
+// center) and half-size; the fragment stage draws each shape procedurally
+// from them. Unlit; alpha-blended over the opaque scene.
+
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec3<f32>,
+    point_light_count: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+// Mirrors DecalShape in renderer/decals.rs.
+const SHAPE_FOOTPRINT: u32 = 0u;
+const SHAPE_RALLY: u32 = 1u;
+const SHAPE_SCORCH: u32 = 2u;
+
+// Footprint border width and grid line width, in world units.
+const FOOTPRINT_BORDER: f32 = 0.12;
+const FOOTPRINT_GRID: f32 = 0.04;
+// Rally ring thickness and center dot radius as fractions of the radius.
+const RALLY_RING_WIDTH: f32 = 0.14;
+const RALLY_DOT: f32 = 0.22;
+
+struct DecalInput {
+    @location(0) position: vec3<f32>,
+    @location(1) shape: u32,
+    // xy = decal-local offset, zw = half-size.
+    @location(2) local_half: vec4<f32>,
+    @location(3) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) half_size: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) @interpolate(flat) shape: u32,
+    // Decal center in world X/Z: seeds the scorch noise per decal.
+    @location(4) @interpolate(flat) center: vec2<f32>,
+}
+
+@vertex
+fn vs_decal(decal: DecalInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_proj * vec4<f32>(decal.position, 1.0);
+    out.local = decal.local_half.xy;
+    out.half_size = decal.local_half.zw;
+    out.color = decal.color;
+    out.shape = decal.shape;
+    out.center = decal.position.xz - decal.local_half.xy;
+    return out;
+}
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+fn value_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let bottom = mix(hash(i), hash(i + vec2<f32>(1.0, 0.0)), u.x);
+    let top = mix(hash(i + vec2<f32>(0.0, 1.0)), hash(i + vec2<f32>(1.0, 1.0)), u.x);
+    return mix(bottom, top, u.y);
+}
+
+// 1 inside `d < edge`, 0 outside, anti-aliased over one pixel of `d`.
+fn inside(d: f32, edge: f32) -> f32 {
+    let aa = fwidth(d);
+    return 1.0 - smoothstep(edge - aa, edge + aa, d);
+}
+
+// Translucent fill, solid border, faint lines on the tile grid.
+fn footprint(local: vec2<f32>, half_size: vec2<f32>) -> f32 {
+    let to_edge = half_size - abs(local);
+    let border = inside(min(to_edge.x, to_edge.y), FOOTPRINT_BORDER);
+    let cell = abs(fract(local + half_size + 0.5) - 0.5);
+    let grid = inside(min(cell.x, cell.y), FOOTPRINT_GRID * 0.5);
+    return max(border, max(grid * 0.6, 0.3));
+}
+
+// Ring at the rim plus a dot in the middle.
+fn rally(local: vec2<f32>, half_size: vec2<f32>) -> f32 {
+    let d = length(local / half_size);
+    let ring = inside(d, 1.0) * (1.0 - inside(d, 1.0 - RALLY_RING_WIDTH));
+    let center_dot = inside(d, RALLY_DOT);
+    return max(ring, center_dot);
+}
+
+// Dark blotch, densest in the middle, with a noisy rim.
+fn scorch(local: vec2<f32>, half_size: vec2<f32>, center: vec2<f32>) -> f32 {
+    let d = length(local / half_size);
+    let seed = center * 3.7;
+    let rim = 0.75 + 0.25 * value_noise(local * 2.0 + seed);
+    let speckle = value_noise(local * 6.0 - seed);
+    let core = 1.0 - smoothstep(0.35, 1.0, d / rim);
+    return core * (0.7 + 0.3 * speckle) * inside(d, rim);
+}
+
+@fragment
+fn fs_decal(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Every shape is evaluated and one selected: the patterns use fwidth,
+    // which needs uniform control flow.
+    let shapes = vec3<f32>(
+        footprint(in.local, in.half_size),
+        rally(in.local, in.half_size),
+        scorch(in.local, in.half_size, in.center),
+    );
+    var coverage = shapes[min(in.shape, SHAPE_SCORCH)];
+    // Nothing outside the decal rectangle, whatever the shape.
+    coverage *= f32(all(abs(in.local) <= in.half_size));
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
@@ -1,32 +1,96 @@
 // Instanced rendering shader with Blinn-Phong lighting
-// Each instance has its own position and color
+// Each instance has its own model matrix, color tint, emissive strength and
+// material index; surface parameters come from the material table.
+// Lit by one shadowed directional light plus up to MAX_POINT_LIGHTS point lights.
+// Ground materials additionally blend the terrain splat layers (bind group 3).
+// Toon materials band the lighting; outlined materials are redrawn as
+// inverted hulls by vs_outline/fs_outline.
 
 // Camera uniforms (bind group 0)
 struct Uniforms {
     view_proj: mat4x4<f32>,
     camera_pos: vec3<f32>,
+    point_light_count: u32,
 }
 
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
-// Light uniforms (bind group 1)
+// Light uniforms + shadow map (bind group 1)
 struct Light {
     direction: vec3<f32>,
     color: vec3<f32>,
+    view_proj: mat4x4<f32>,
 }
 
 @group(1) @binding(0)
 var<uniform> light: Light;
 
+@group(1) @binding(1)
+var shadow_map: texture_depth_2d;
+
+@group(1) @binding(2)
+var shadow_sampler: sampler_comparison;
+
+struct PointLight {
+    position: vec3<f32>,
+    radius: f32,
+    color: vec3<f32>,
+}
+
+@group(1) @binding(3)
+var<storage, read> point_lights: array<PointLight>;
+
+// Material table (bind group 2), indexed per instance
+struct Material {
+    base_color: vec4<f32>,
+    specular: f32,
+    shininess: f32,
+    emissive: f32,
+    flags: u32,
+    // rgb = outline color, a = outline width (0 = none)
+    outline: vec4<f32>,
+}
+
+// Material::flags bits (see renderer/material.rs)
+const MATERIAL_SPLAT: u32 = 1u;
+const MATERIAL_TOON: u32 = 2u;
+
+// Distinct diffuse levels of toon lighting (lit side only).
+const TOON_BANDS: f32 = 3.0;
+
+@group(2) @binding(0)
+var<storage, read> materials: array<Material>;
+
+// Terrain splat map (bind group 3): per-tile coverage of three layers in RGB
+struct Splat {
+    // xy = world X/Z origin, zw = 1 / world extent
+    rect: vec4<f32>,
+    layer_colors: array<vec4<f32>, 3>,
+}
+
+@group(3) @binding(0)
+var<uniform> splat: Splat;
+
+@group(3) @binding(1)
+var splat_map: texture_2d<f32>;
+
+@group(3) @binding(2)
+var splat_sampler: sampler;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
 }
 
 struct InstanceInput {
-    @location(2) instance_position: vec3<f32>,
-    @location(3) instance_color: vec4<f32>,
+    @location(2) model_0: vec4<f32>,
+    @location(3) model_1: vec4<f32>,
+    @location(4) model_2: vec4<f32>,
+    @location(5) model_3: vec4<f32>,
+    @location(6) instance_color: vec4<f32>,
+    @location(7) emissive: f32,
+    @location(8) material: u32,
 }
 
 struct VertexOutput {
@@ -34,6 +98,8 @@ struct VertexOutput {
     @location(0) world_position: vec3<f32>,
     @location(1) world_normal: vec3<f32>,
     @location(2) color: vec4<f32>,
+    @location(3) emissive: f32,
+    @location(4) @interpolate(flat) material: u32,
 }
 
 @vertex
@@ -43,40 +109,175 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
 
-    // Offset vertex position by instance position
-    let world_position = vertex.position + instance.instance_position;
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let world_position = (model * vec4<f32>(vertex.position, 1.0)).xyz;
     out.clip_position = uniforms.view_proj * vec4<f32>(world_position, 1.0);
     out.world_position = world_position;
-    out.world_normal = vertex.normal;  // Cubes don't rotate, so normal is unchanged
+    out.world_normal = normal_to_world(model, vertex.normal);
     out.color = instance.instance_color;
+    out.emissive = instance.emissive;
+    out.material = instance.material;
+
+    return out;
+}
+
+// Normal matrix for a scale-rotate-translate model: R·S⁻¹ = M₃·S⁻².
+// Column lengths² of M₃ are the squared scales, so no matrix inverse is needed.
+fn normal_to_world(model: mat4x4<f32>, normal: vec3<f32>) -> vec3<f32> {
+    let m3 = mat3x3<f32>(model[0].xyz, model[1].xyz, model[2].xyz);
+    let scale_sq = vec3<f32>(dot(m3[0], m3[0]), dot(m3[1], m3[1]), dot(m3[2], m3[2]));
+    return normalize(m3 * (normal / scale_sq));
+}
+
+struct OutlineOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+}
+
+// Inverted hull: the mesh pushed out along its normals, drawn with front faces
+// culled so only the rim around the silhouette survives the depth test.
+// Batches mix materials, so instances without an outline are collapsed
+// outside the clip volume.
+@vertex
+fn vs_outline(vertex: VertexInput, instance: InstanceInput) -> OutlineOutput {
+    let outline = materials[instance.material].outline;
+    var out: OutlineOutput;
+    out.color = outline.rgb;
+    if (outline.a <= 0.0) {
+        out.clip_position = vec4<f32>(2.0, 2.0, 2.0, 1.0);
+        return out;
+    }
 
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let world_position = (model * vec4<f32>(vertex.position, 1.0)).xyz;
+    let expanded = world_position + normal_to_world(model, vertex.normal) * outline.a;
+    out.clip_position = uniforms.view_proj * vec4<f32>(expanded, 1.0);
     return out;
 }
 
+@fragment
+fn fs_outline(in: OutlineOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color, 1.0);
+}
+
+// Toon diffuse: 0 in shade, then TOON_BANDS - 1 flat steps up to full light.
+fn toon_band(x: f32) -> f32 {
+    return min(floor(x * TOON_BANDS) / (TOON_BANDS - 1.0), 1.0);
+}
+
+// Fraction of the sun reaching `world_position`: 3x3 PCF over the shadow map.
+// Points outside the light frustum are treated as lit.
+fn shadow_factor(world_position: vec3<f32>) -> f32 {
+    let light_clip = light.view_proj * vec4<f32>(world_position, 1.0);
+    let ndc = light_clip.xyz / light_clip.w;
+    let uv = vec2<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+    let texel = 1.0 / vec2<f32>(textureDimensions(shadow_map));
+
+    var lit = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * texel;
+            lit += textureSampleCompareLevel(shadow_map, shadow_sampler, uv + offset, ndc.z);
+        }
+    }
+    lit /= 9.0;
+
+    let outside = any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0;
+    return select(lit, 1.0, outside);
+}
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+// Smooth value noise in [0, 1] with one lattice cell per world unit of `p`.
+fn value_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let bottom = mix(hash(i), hash(i + vec2<f32>(1.0, 0.0)), u.x);
+    let top = mix(hash(i + vec2<f32>(0.0, 1.0)), hash(i + vec2<f32>(1.0, 1.0)), u.x);
+    return mix(bottom, top, u.y);
+}
+
+// Paint the splat layers over `base`. Bilinear tile coverage is pushed
+// through a noisy threshold so blends between tiles get ragged edges instead
+// of soft squares; steep faces (cliff walls) always show layer 1 (rock).
+// Sampled with an explicit LOD: the material branch is not uniform.
+fn apply_splat(base: vec3<f32>, world_position: vec3<f32>, normal: vec3<f32>) -> vec3<f32> {
+    let uv = (world_position.xz - splat.rect.xy) * splat.rect.zw;
+    let coverage = textureSampleLevel(splat_map, splat_sampler, uv, 0.0).rgb;
+    let n = 0.65 * value_noise(world_position.xz * 1.3) + 0.35 * value_noise(world_position.xz * 4.1);
+    let weights = clamp((coverage - 0.5) * 3.0 + 0.5 + (n - 0.5) * 1.5, vec3<f32>(0.0), vec3<f32>(1.0));
+    let steep = 1.0 - smoothstep(0.5, 0.8, normal.y);
+    let tint = 0.85 + 0.3 * n;
+
+    var color = base * tint;
+    color = mix(color, splat.layer_colors[0].rgb * tint, weights.x);
+    color = mix(color, splat.layer_colors[1].rgb * tint, max(weights.y, steep));
+    color = mix(color, splat.layer_colors[2].rgb, weights.z);
+    return color;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let material = materials[in.material];
+
     // Normalize inputs
     let normal = normalize(in.world_normal);
+
+    var surface = in.color * material.base_color;
+    if ((material.flags & MATERIAL_SPLAT) != 0u) {
+        surface = vec4<f32>(apply_splat(surface.rgb, in.world_position, normal), surface.a);
+    }
     let light_dir = normalize(-light.direction);  // Negate because direction points away from light
+    let toon = (material.flags & MATERIAL_TOON) != 0u;
 
     // Ambient lighting
     let ambient_strength = 0.1;
     let ambient = light.color * ambient_strength;
 
     // Diffuse lighting
-    let diff = max(dot(normal, light_dir), 0.0);
+    let lambert = max(dot(normal, light_dir), 0.0);
+    let diff = select(lambert, toon_band(lambert), toon);
     let diffuse = light.color * diff;
 
     // Specular lighting (Blinn-Phong)
     let view_dir = normalize(uniforms.camera_pos - in.world_position);
     let half_dir = normalize(view_dir + light_dir);
-    let spec = pow(max(dot(normal, half_dir), 0.0), 32.0);  // 32 = shininess
-    let specular_strength = 0.5;
+    let phong = pow(max(dot(normal, half_dir), 0.0), material.shininess);
+    let spec = select(phong, step(0.5, phong), toon);
+    let specular_strength = material.specular;
     let specular = light.color * spec * specular_strength;
 
-    // Combine lighting with object color
-    let lighting = ambient + diffuse + specular;
-    let result = lighting * in.color.rgb;
+    // Point lights: unshadowed, windowed inverse-square-ish falloff to 0 at radius
+    var point = vec3<f32>(0.0);
+    for (var i = 0u; i < uniforms.point_light_count; i++) {
+        let pl = point_lights[i];
+        let to_light = pl.position - in.world_position;
+        let dist = length(to_light);
+        if (dist >= pl.radius) {
+            continue;
+        }
+        let l = to_light / max(dist, 1e-4);
+        let falloff = 1.0 - (dist * dist) / (pl.radius * pl.radius);
+        let attenuation = falloff * falloff;
+        let h = normalize(view_dir + l);
+        var p_diff = max(dot(normal, l), 0.0) * attenuation;
+        var p_spec = pow(max(dot(normal, h), 0.0), material.shininess) * attenuation;
+        if (toon) {
+            p_diff = toon_band(p_diff);
+            p_spec = step(0.5, p_spec);
+        }
+        point += pl.color * (p_diff + p_spec * specular_strength);
+    }
+
+    // Combine lighting with surface color; shadows only block direct light
+    let pcf = shadow_factor(in.world_position);
+    let shadow = select(pcf, step(0.5, pcf), toon);
+    let lighting = ambient + (diffuse + specular) * shadow + point;
+    let emissive = in.emissive + material.emissive;
+    let result = lighting * surface.rgb + surface.rgb * emissive;
 
-    return vec4<f32>(result, in.color.a);
+    return vec4<f32>(result, surface.a);
 }
@@ -0,0 +1,53 @@
+// Particle billboards: one camera-facing quad (6 vertices) per instance.
+// Shares the scene's camera uniforms; no lighting, color is emitted as-is.
+
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec3<f32>,
+    point_light_count: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct ParticleInput {
+    @location(0) position_size: vec4<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_particle(@builtin(vertex_index) vertex_index: u32, particle: ParticleInput) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+
+    // For a symmetric projection, rows 0 and 1 of view_proj are the camera's
+    // world-space right and up axes scaled by the projection.
+    let m = uniforms.view_proj;
+    let right = normalize(vec3<f32>(m[0].x, m[1].x, m[2].x));
+    let up = normalize(vec3<f32>(m[0].y, m[1].y, m[2].y));
+
+    let half_size = 0.5 * particle.position_size.w;
+    let world = particle.position_size.xyz + (right * corner.x + up * corner.y) * half_size;
+
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_proj * vec4<f32>(world, 1.0);
+    out.uv = corner;
+    out.color = particle.color;
+    return out;
+}
+
+@fragment
+fn fs_particle(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Soft round sprite: quadratic falloff from the center to the inscribed circle.
+    let falloff = clamp(1.0 - length(in.uv), 0.0, 1.0);
+    return vec4<f32>(in.color.rgb, in.color.a * falloff * falloff);
+}
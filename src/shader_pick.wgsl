@@ -0,0 +1,45 @@
+// Entity-ID pass: writes each opaque instance's 64-bit pick ID (low, high
+// words) into an Rg32Uint target. Shares vertex/instance layouts with
+// shader_instanced.wgsl; no lighting, no blending. ID 0 means "nothing
+// pickable here".
+
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec3<f32>,
+    point_light_count: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct InstanceInput {
+    @location(2) model_0: vec4<f32>,
+    @location(3) model_1: vec4<f32>,
+    @location(4) model_2: vec4<f32>,
+    @location(5) model_3: vec4<f32>,
+    @location(9) pick_id: vec2<u32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) @interpolate(flat) pick_id: vec2<u32>,
+}
+
+@vertex
+fn vs_pick(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_proj * model * vec4<f32>(vertex.position, 1.0);
+    out.pick_id = instance.pick_id;
+    return out;
+}
+
+@fragment
+fn fs_pick(in: VertexOutput) -> @location(0) vec2<u32> {
+    return in.pick_id;
+}
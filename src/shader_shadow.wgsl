@@ -0,0 +1,28 @@
+// Depth-only shadow pass: transforms instanced geometry into light clip space.
+// Shares vertex/instance layouts with shader_instanced.wgsl; no fragment stage.
+
+struct ShadowUniforms {
+    light_view_proj: mat4x4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> shadow: ShadowUniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct InstanceInput {
+    @location(2) model_0: vec4<f32>,
+    @location(3) model_1: vec4<f32>,
+    @location(4) model_2: vec4<f32>,
+    @location(5) model_3: vec4<f32>,
+    @location(6) instance_color: vec4<f32>,
+}
+
+@vertex
+fn vs_shadow(vertex: VertexInput, instance: InstanceInput) -> @builtin(position) vec4<f32> {
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    return shadow.light_view_proj * model * vec4<f32>(vertex.position, 1.0);
+}
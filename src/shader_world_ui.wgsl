@@ -0,0 +1,103 @@
+// World-space UI: selection rings flat on the ground and billboarded
+// health/shield bars above units. One instance format, two vertex/fragment
+// entry pairs (one draw call each). Unlit; colors are written as-is.
+
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec3<f32>,
+    point_light_count: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct UiInput {
+    // Ring: ground center + outer radius. Bar: anchor (bar center) + width.
+    @location(0) position_size: vec4<f32>,
+    @location(1) color: vec4<f32>,
+    // Bar only: health and shield fill in [0, 1]; shield < 0 means no shield row.
+    @location(2) fill: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) fill: vec2<f32>,
+}
+
+// Bar height as a fraction of its width.
+const BAR_ASPECT: f32 = 0.16;
+// Ring thickness as a fraction of its radius.
+const RING_WIDTH: f32 = 0.18;
+
+fn quad_corner(vertex_index: u32) -> vec2<f32> {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    return corners[vertex_index];
+}
+
+@vertex
+fn vs_ring(@builtin(vertex_index) vertex_index: u32, ui: UiInput) -> VertexOutput {
+    let corner = quad_corner(vertex_index);
+    let world = ui.position_size.xyz + vec3<f32>(corner.x, 0.0, corner.y) * ui.position_size.w;
+
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_proj * vec4<f32>(world, 1.0);
+    out.uv = corner;
+    out.color = ui.color;
+    out.fill = ui.fill;
+    return out;
+}
+
+@fragment
+fn fs_ring(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Anti-aliased annulus between (1 - RING_WIDTH) and 1 in quad space.
+    let d = length(in.uv);
+    let aa = fwidth(d);
+    let outer = 1.0 - smoothstep(1.0 - aa, 1.0, d);
+    let inner = smoothstep(1.0 - RING_WIDTH - aa, 1.0 - RING_WIDTH, d);
+    return vec4<f32>(in.color.rgb, in.color.a * outer * inner);
+}
+
+@vertex
+fn vs_bar(@builtin(vertex_index) vertex_index: u32, ui: UiInput) -> VertexOutput {
+    let corner = quad_corner(vertex_index);
+
+    // Camera right/up from rows 0/1 of view_proj (see shader_particles.wgsl).
+    let m = uniforms.view_proj;
+    let right = normalize(vec3<f32>(m[0].x, m[1].x, m[2].x));
+    let up = normalize(vec3<f32>(m[0].y, m[1].y, m[2].y));
+
+    let half_width = 0.5 * ui.position_size.w;
+    let offset = right * corner.x * half_width + up * corner.y * half_width * BAR_ASPECT;
+
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_proj * vec4<f32>(ui.position_size.xyz + offset, 1.0);
+    out.uv = corner * 0.5 + 0.5;
+    out.color = ui.color;
+    out.fill = ui.fill;
+    return out;
+}
+
+@fragment
+fn fs_bar(in: VertexOutput) -> @location(0) vec4<f32> {
+    let background = vec4<f32>(0.05, 0.05, 0.05, 0.85);
+    let shield_color = vec4<f32>(0.35, 0.65, 1.0, 1.0);
+
+    // 1-texel-ish dark frame: inset the fill region slightly.
+    let inset = vec2<f32>(0.02, 0.12);
+    let inner = (in.uv - inset) / (1.0 - 2.0 * inset);
+    if (any(inner < vec2<f32>(0.0)) || any(inner > vec2<f32>(1.0))) {
+        return background;
+    }
+
+    // With a shield, the top third of the bar shows it and the rest health.
+    let has_shield = in.fill.y >= 0.0;
+    if (has_shield && inner.y > 0.66) {
+        return select(background, shield_color, inner.x <= in.fill.y);
+    }
+    return select(background, in.color, inner.x <= in.fill.x);
+}